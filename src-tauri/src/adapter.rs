@@ -0,0 +1,707 @@
+//! The clients whose MCP servers the app manages.
+//!
+//! Each supported tool is a [`ToolAdapter`]: how to list and edit its servers
+//! and how its entries convert to and from the canonical server model.
+//! Commands look tools up by the `source` id the frontend sends and reject
+//! ids no adapter is registered for.
+//!
+//! The canonical model is a Claude Code `mcpServers` entry: every tool
+//! converts into it, so converting between two tools is always
+//! `to.to_native(from.to_canonical(entry))`.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::error::ConfigError;
+use crate::refs::Syntax;
+use crate::sidecar::Sidecar;
+use crate::{validate, ConversionReport, MCPItem, McpScope, McpServers};
+
+/// Mutation of the servers of one scope: gets `None` when the scope does not
+/// exist and returns the previous value of the entry it changed.
+pub type Mutate<'a> =
+    dyn FnMut(Option<&mut McpServers>) -> Result<Option<Value>, ConfigError> + 'a;
+
+pub trait ToolAdapter: Sync {
+    /// Id used as `source` by the frontend, in backups and in the history.
+    fn id(&self) -> &'static str;
+
+    /// Name of the tool for messages.
+    fn label(&self) -> &'static str;
+
+    /// Name of the config file `scope` lives in, for messages.
+    fn scope_label(&self, _scope: &McpScope) -> String {
+        self.label().to_string()
+    }
+
+    /// Every server of the tool, in every scope it has.
+    fn list(&self) -> Result<Vec<MCPItem>, ConfigError>;
+
+    /// The user scope servers.
+    fn load_servers(&self) -> Result<McpServers, ConfigError>;
+
+    /// The file the user scope servers live in.
+    fn user_path(&self) -> PathBuf;
+
+    /// Load, mutate and save the servers of `scope` without clobbering
+    /// concurrent edits. With `create`, a missing scope is added first.
+    fn update_servers(
+        &self,
+        scope: &McpScope,
+        create: bool,
+        mutate: &mut Mutate,
+    ) -> Result<Option<Value>, ConfigError>;
+
+    /// Fill in what the editor leaves out of an entry before it is written.
+    fn prepare_entry(&self, _config: &mut Value, _description: Option<String>, _adding: bool) {}
+
+    /// Tools without a way to switch a server off refuse by default.
+    fn set_enabled(&self, name: &str, _scope: &McpScope, _enabled: bool) -> Result<(), ConfigError> {
+        Err(ConfigError::InvalidInput(format!(
+            "{} cannot disable MCP '{}'; delete it instead",
+            self.label(),
+            name
+        )))
+    }
+
+    /// Like `update_servers`, for the servers of `scope` the tool does not see
+    /// while they are disabled. `mutate` gets `None` when there are none.
+    fn update_disabled(&self, _scope: &McpScope, mutate: &mut Mutate) -> Result<Option<Value>, ConfigError> {
+        mutate(None)
+    }
+
+    /// Problems that would keep the tool from loading entry `config`.
+    fn validate(&self, name: &str, config: &Value) -> Vec<String>;
+
+    fn to_canonical(&self, config: &Value) -> (Value, ConversionReport);
+
+    fn to_native(&self, canonical: &Value) -> (Value, ConversionReport);
+
+    /// Fields of the tool's entries that conversion carries to other tools;
+    /// the sidecar keeps the rest.
+    fn mapped_fields(&self) -> &'static [&'static str];
+}
+
+pub struct OpenCode;
+pub struct ClaudeCode;
+pub struct ClaudeDesktop;
+pub struct Cursor;
+
+pub const OPENCODE: &dyn ToolAdapter = &OpenCode;
+pub const CLAUDE: &dyn ToolAdapter = &ClaudeCode;
+pub const CLAUDE_DESKTOP: &dyn ToolAdapter = &ClaudeDesktop;
+pub const CURSOR: &dyn ToolAdapter = &Cursor;
+
+/// Every supported tool.
+pub static ADAPTERS: [&dyn ToolAdapter; 4] = [OPENCODE, CLAUDE, CLAUDE_DESKTOP, CURSOR];
+
+/// The adapter of `source`.
+pub fn adapter(source: &str) -> Result<&'static dyn ToolAdapter, ConfigError> {
+    ADAPTERS
+        .iter()
+        .copied()
+        .find(|adapter| adapter.id() == source)
+        .ok_or_else(|| ConfigError::InvalidInput(format!("Unknown MCP source: {}", source)))
+}
+
+/// Convert entry `config` of server `name` from `from`'s format in
+/// `from_scope` to `to`'s in `to_scope`, restoring what the conversion
+/// cannot carry from `sidecar`.
+pub fn convert(
+    from: &dyn ToolAdapter,
+    from_scope: &McpScope,
+    to: &dyn ToolAdapter,
+    to_scope: &McpScope,
+    sidecar: &mut Sidecar,
+    name: &str,
+    config: &Value,
+) -> (Value, ConversionReport) {
+    let (canonical, mut report) = from.to_canonical(config);
+    let (plain, native_report) = to.to_native(&canonical);
+    report.append(native_report);
+
+    let converted = sidecar.restore(
+        name,
+        (from.id(), from_scope),
+        (to.id(), to_scope),
+        config,
+        plain.clone(),
+        to.mapped_fields(),
+    );
+    report.note_restored(&plain, &converted);
+    (converted, report)
+}
+
+/// A list entry for server `name` of `source`.
+pub fn mcp_item(name: String, config: &Value, source: &str, scope: McpScope) -> MCPItem {
+    MCPItem {
+        name,
+        config: serde_json::to_string_pretty(config).unwrap_or_else(|_| config.to_string()),
+        source: source.to_string(),
+        enabled: true,
+        description: None,
+        scope,
+        read_only: false,
+    }
+}
+
+/// OpenCode fields `convert_opencode_to_claude` translates.
+const OPENCODE_MAPPED: [&str; 7] = ["type", "command", "environment", "url", "headers", "transport", "timeout"];
+
+/// Claude Code fields `convert_claude_to_opencode` translates.
+const CLAUDE_MAPPED: [&str; 9] = [
+    "type",
+    "command",
+    "args",
+    "env",
+    "url",
+    "headers",
+    "transport",
+    "timeout",
+    "description",
+];
+
+/// The only fields of a Claude Desktop entry.
+const CLAUDE_DESKTOP_MAPPED: [&str; 3] = ["command", "args", "env"];
+
+/// Cursor fields that carry over to other tools; `type` is implied by `url`.
+const CURSOR_MAPPED: [&str; 5] = ["command", "args", "env", "url", "headers"];
+
+/// `CURSOR_MAPPED` fields of `entry`, with their variable references
+/// rewritten from `from`'s syntax to `to`'s.
+fn cursor_fields(
+    entry: &serde_json::Map<String, Value>,
+    from: Syntax,
+    to: Syntax,
+    warnings: &mut Vec<String>,
+) -> serde_json::Map<String, Value> {
+    let mut fields = entry
+        .iter()
+        .filter(|(key, _)| CURSOR_MAPPED.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    crate::translate_refs(&mut fields, &CURSOR_MAPPED, from, to, warnings);
+    fields
+}
+
+impl ToolAdapter for OpenCode {
+    fn id(&self) -> &'static str {
+        "opencode"
+    }
+
+    fn label(&self) -> &'static str {
+        "OpenCode"
+    }
+
+    fn list(&self) -> Result<Vec<MCPItem>, ConfigError> {
+        let config = crate::load_opencode_config()?;
+        Ok(config
+            .mcp
+            .into_iter()
+            .map(|(name, config)| MCPItem {
+                enabled: config.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true),
+                description: config
+                    .get("description")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                ..mcp_item(name, &config, self.id(), McpScope::User)
+            })
+            .collect())
+    }
+
+    fn load_servers(&self) -> Result<McpServers, ConfigError> {
+        Ok(crate::load_opencode_config()?.mcp)
+    }
+
+    fn user_path(&self) -> PathBuf {
+        crate::opencode_config_file().path
+    }
+
+    /// OpenCode servers are always user scope; `scope` is ignored.
+    fn update_servers(
+        &self,
+        _scope: &McpScope,
+        _create: bool,
+        mutate: &mut Mutate,
+    ) -> Result<Option<Value>, ConfigError> {
+        crate::update_opencode_config(|config| mutate(Some(&mut config.mcp)))
+    }
+
+    /// New servers are enabled unless they say otherwise; the description
+    /// field of the editor goes into the entry.
+    fn prepare_entry(&self, config: &mut Value, description: Option<String>, adding: bool) {
+        if let Some(obj) = config.as_object_mut() {
+            if adding && !obj.contains_key("enabled") {
+                obj.insert("enabled".to_string(), serde_json::json!(true));
+            }
+            if let Some(desc) = description {
+                obj.insert("description".to_string(), serde_json::json!(desc));
+            }
+        }
+    }
+
+    fn set_enabled(&self, name: &str, _scope: &McpScope, enabled: bool) -> Result<(), ConfigError> {
+        crate::update_opencode_config(|config| {
+            match config.mcp.get_mut(name).and_then(|v| v.as_object_mut()) {
+                Some(obj) => {
+                    obj.insert("enabled".to_string(), serde_json::json!(enabled));
+                    Ok(())
+                }
+                None => Err(ConfigError::mcp_not_found(name, self.label())),
+            }
+        })
+    }
+
+    fn validate(&self, name: &str, config: &Value) -> Vec<String> {
+        validate::opencode_mcp(name, config)
+    }
+
+    fn to_canonical(&self, config: &Value) -> (Value, ConversionReport) {
+        crate::convert_opencode_to_claude_with_report(config)
+    }
+
+    fn to_native(&self, canonical: &Value) -> (Value, ConversionReport) {
+        crate::convert_claude_to_opencode_with_report(canonical)
+    }
+
+    fn mapped_fields(&self) -> &'static [&'static str] {
+        &OPENCODE_MAPPED
+    }
+}
+
+impl ToolAdapter for ClaudeCode {
+    fn id(&self) -> &'static str {
+        "claude"
+    }
+
+    fn label(&self) -> &'static str {
+        "Claude Code"
+    }
+
+    fn scope_label(&self, scope: &McpScope) -> String {
+        scope.claude_label()
+    }
+
+    /// User and local scope servers of `~/.claude.json`, the disabled ones
+    /// the app keeps, those of registered projects' `.mcp.json` and the
+    /// read-only ones of installed plugins. Unreadable files are skipped.
+    fn list(&self) -> Result<Vec<MCPItem>, ConfigError> {
+        let mut items = Vec::new();
+
+        // From mcpServers, then from projects["<path>"].mcpServers
+        if let Ok(config) = crate::load_claude_config() {
+            let scopes = std::iter::once((McpScope::User, config.mcp_servers)).chain(
+                config
+                    .projects
+                    .into_iter()
+                    .map(|(path, project)| (McpScope::Local { path }, project.mcp_servers)),
+            );
+            for (scope, servers) in scopes {
+                for (name, config) in servers {
+                    items.push(mcp_item(name, &config, self.id(), scope.clone()));
+                }
+            }
+        }
+
+        // Disabled user and local scope servers, kept by the app
+        match crate::disabled_mcp::load(&crate::get_disabled_mcp_path()) {
+            Ok(store) => {
+                for server in store.servers {
+                    items.push(MCPItem {
+                        enabled: false,
+                        ..mcp_item(server.name, &server.config, self.id(), server.scope)
+                    });
+                }
+            }
+            Err(e) => log::warn!("Could not load disabled MCPs: {}", e),
+        }
+
+        // Project servers from the .mcp.json of each registered project
+        for project in crate::load_app_settings().projects {
+            let file = match crate::project_mcp_file(Path::new(&project)) {
+                Ok(file) if file.path.exists() => file,
+                Ok(_) => continue,
+                Err(e) => {
+                    log::warn!("Skipping project {}: {}", project, e);
+                    continue;
+                }
+            };
+            let config = match file.load() {
+                Ok(config) => config,
+                Err(e) => {
+                    log::warn!("Skipping project {}: {}", project, e);
+                    continue;
+                }
+            };
+            let disabled = crate::project_disabled_servers(&project);
+            for (name, config) in config.mcp_servers {
+                let scope = McpScope::Project {
+                    path: project.clone(),
+                };
+                items.push(MCPItem {
+                    enabled: !disabled.contains(&name),
+                    ..mcp_item(name, &config, self.id(), scope)
+                });
+            }
+        }
+
+        // Servers shipped by plugins (never edited)
+        for server in crate::plugins::discover(&crate::get_claude_plugins_directory()) {
+            let scope = McpScope::Plugin {
+                plugin: server.plugin,
+                path: server.path.to_string_lossy().to_string(),
+            };
+            items.push(MCPItem {
+                read_only: true,
+                ..mcp_item(server.name, &server.config, self.id(), scope)
+            });
+        }
+
+        Ok(items)
+    }
+
+    fn load_servers(&self) -> Result<McpServers, ConfigError> {
+        Ok(crate::load_claude_config()?.mcp_servers)
+    }
+
+    fn user_path(&self) -> PathBuf {
+        crate::claude_config_file().path
+    }
+
+    fn update_servers(
+        &self,
+        scope: &McpScope,
+        create: bool,
+        mutate: &mut Mutate,
+    ) -> Result<Option<Value>, ConfigError> {
+        crate::update_claude_servers(scope, create, mutate)
+    }
+
+    /// User and local scope servers move between `~/.claude.json` and the
+    /// app's disabled store; project servers stay in `.mcp.json` and are
+    /// listed in the project's `.claude/settings.local.json` instead.
+    fn set_enabled(&self, name: &str, scope: &McpScope, enabled: bool) -> Result<(), ConfigError> {
+        match scope {
+            McpScope::Project { path } => crate::toggle_project_server(name, path, enabled),
+            _ => crate::toggle_claude_server(name, scope, enabled),
+        }
+    }
+
+    fn update_disabled(&self, scope: &McpScope, mutate: &mut Mutate) -> Result<Option<Value>, ConfigError> {
+        crate::update_disabled_servers(&crate::get_disabled_mcp_path(), scope, mutate)
+    }
+
+    fn validate(&self, name: &str, config: &Value) -> Vec<String> {
+        validate::claude_mcp(name, config)
+    }
+
+    fn to_canonical(&self, config: &Value) -> (Value, ConversionReport) {
+        (config.clone(), ConversionReport::default())
+    }
+
+    fn to_native(&self, canonical: &Value) -> (Value, ConversionReport) {
+        (canonical.clone(), ConversionReport::default())
+    }
+
+    fn mapped_fields(&self) -> &'static [&'static str] {
+        &CLAUDE_MAPPED
+    }
+}
+
+impl ToolAdapter for ClaudeDesktop {
+    fn id(&self) -> &'static str {
+        "claude-desktop"
+    }
+
+    fn label(&self) -> &'static str {
+        "Claude Desktop"
+    }
+
+    fn list(&self) -> Result<Vec<MCPItem>, ConfigError> {
+        Ok(self
+            .load_servers()?
+            .into_iter()
+            .map(|(name, config)| mcp_item(name, &config, self.id(), McpScope::User))
+            .collect())
+    }
+
+    fn load_servers(&self) -> Result<McpServers, ConfigError> {
+        Ok(crate::claude_desktop_config_file().load()?.mcp_servers)
+    }
+
+    fn user_path(&self) -> PathBuf {
+        crate::claude_desktop_config_file().path
+    }
+
+    /// Claude Desktop servers are always user scope; `scope` is ignored.
+    fn update_servers(
+        &self,
+        _scope: &McpScope,
+        _create: bool,
+        mutate: &mut Mutate,
+    ) -> Result<Option<Value>, ConfigError> {
+        crate::claude_desktop_config_file().update(|config| mutate(Some(&mut config.mcp_servers)))
+    }
+
+    fn validate(&self, name: &str, config: &Value) -> Vec<String> {
+        validate::claude_desktop_mcp(name, config)
+    }
+
+    /// A Claude Desktop entry is already a valid Claude Code stdio entry.
+    fn to_canonical(&self, config: &Value) -> (Value, ConversionReport) {
+        (config.clone(), ConversionReport::default())
+    }
+
+    /// Stdio entries keep `command`, `args` and `env`, with a warning for
+    /// each variable reference, which Claude Desktop would pass on literally.
+    /// Remote servers cannot be expressed and are passed through with a
+    /// warning, so validation rejects them.
+    fn to_native(&self, canonical: &Value) -> (Value, ConversionReport) {
+        let mut report = ConversionReport::default();
+        let (Some(entry), Some(crate::McpTransport::Stdio) | None) =
+            (canonical.as_object(), crate::McpTransport::of_claude(canonical))
+        else {
+            let url = canonical.get("url").and_then(|u| u.as_str()).unwrap_or_default();
+            report.warnings.push(format!(
+                "Claude Desktop only runs stdio servers; the remote server at {} cannot be synced to it",
+                url
+            ));
+            return (canonical.clone(), report);
+        };
+
+        report.dropped = crate::dropped_fields(canonical, &["type", "command", "args", "env"]);
+        let mut native = entry
+            .iter()
+            .filter(|(key, _)| CLAUDE_DESKTOP_MAPPED.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        crate::translate_refs(
+            &mut native,
+            &CLAUDE_DESKTOP_MAPPED,
+            Syntax::Claude,
+            Syntax::ClaudeDesktop,
+            &mut report.warnings,
+        );
+        (Value::Object(native), report)
+    }
+
+    fn mapped_fields(&self) -> &'static [&'static str] {
+        &CLAUDE_DESKTOP_MAPPED
+    }
+}
+
+impl ToolAdapter for Cursor {
+    fn id(&self) -> &'static str {
+        "cursor"
+    }
+
+    fn label(&self) -> &'static str {
+        "Cursor"
+    }
+
+    fn scope_label(&self, scope: &McpScope) -> String {
+        match scope {
+            McpScope::Project { path } => format!("{}/.cursor/mcp.json", path.trim_end_matches('/')),
+            _ => self.label().to_string(),
+        }
+    }
+
+    /// Servers of `~/.cursor/mcp.json` and of the `.cursor/mcp.json` of each
+    /// registered project. Unreadable project files are skipped.
+    fn list(&self) -> Result<Vec<MCPItem>, ConfigError> {
+        let mut items: Vec<MCPItem> = self
+            .load_servers()?
+            .into_iter()
+            .map(|(name, config)| mcp_item(name, &config, self.id(), McpScope::User))
+            .collect();
+
+        for project in crate::load_app_settings().projects {
+            let config = match crate::cursor_project_file(Path::new(&project)) {
+                Ok(file) if !file.path.exists() => continue,
+                Ok(file) => file.load(),
+                Err(e) => Err(e),
+            };
+            match config {
+                Ok(config) => {
+                    for (name, config) in config.mcp_servers {
+                        let scope = McpScope::Project {
+                            path: project.clone(),
+                        };
+                        items.push(mcp_item(name, &config, self.id(), scope));
+                    }
+                }
+                Err(e) => log::warn!("Skipping Cursor servers of project {}: {}", project, e),
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn load_servers(&self) -> Result<McpServers, ConfigError> {
+        Ok(crate::cursor_config_file().load()?.mcp_servers)
+    }
+
+    fn user_path(&self) -> PathBuf {
+        crate::cursor_config_file().path
+    }
+
+    /// User scope is `~/.cursor/mcp.json`, project scope the project's
+    /// `.cursor/mcp.json`; both files are created when missing.
+    fn update_servers(
+        &self,
+        scope: &McpScope,
+        _create: bool,
+        mutate: &mut Mutate,
+    ) -> Result<Option<Value>, ConfigError> {
+        let file = match scope {
+            McpScope::User => crate::cursor_config_file(),
+            McpScope::Project { path } => crate::cursor_project_file(Path::new(path))?,
+            McpScope::Local { .. } | McpScope::Plugin { .. } => {
+                return Err(ConfigError::InvalidInput(
+                    "Cursor servers are either global or in a project's .cursor/mcp.json".to_string(),
+                ))
+            }
+        };
+        file.update(|config| mutate(Some(&mut config.mcp_servers)))
+    }
+
+    fn validate(&self, name: &str, config: &Value) -> Vec<String> {
+        validate::cursor_mcp(name, config)
+    }
+
+    /// Remote entries get the `type` Claude Code needs, from an explicit
+    /// one or the URL; `${env:VAR}` becomes `${VAR}`.
+    fn to_canonical(&self, config: &Value) -> (Value, ConversionReport) {
+        let mut report = ConversionReport::default();
+        let Some(entry) = config.as_object() else {
+            return (config.clone(), report);
+        };
+
+        let mut canonical = cursor_fields(entry, Syntax::Cursor, Syntax::Claude, &mut report.warnings);
+        if let Some(transport) = crate::McpTransport::of_claude(config) {
+            if !entry.contains_key("type") {
+                report.inferred.push("type".to_string());
+            }
+            canonical.insert("type".to_string(), Value::from(transport.claude_type()));
+        }
+        report.dropped = crate::dropped_fields(config, &["type", "command", "args", "env", "url", "headers"]);
+        (Value::Object(canonical), report)
+    }
+
+    /// Cursor tells stdio from remote servers by `url` and negotiates the
+    /// remote transport itself, so `type` is left out.
+    fn to_native(&self, canonical: &Value) -> (Value, ConversionReport) {
+        let mut report = ConversionReport::default();
+        let Some(entry) = canonical.as_object() else {
+            return (canonical.clone(), report);
+        };
+
+        let native = cursor_fields(entry, Syntax::Claude, Syntax::Cursor, &mut report.warnings);
+        report.dropped = crate::dropped_fields(canonical, &["type", "command", "args", "env", "url", "headers"]);
+        (Value::Object(native), report)
+    }
+
+    fn mapped_fields(&self) -> &'static [&'static str] {
+        &CURSOR_MAPPED
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_registry_rejects_unknown_sources() {
+        assert_eq!(adapter("opencode").unwrap().label(), "OpenCode");
+        assert_eq!(adapter("claude").unwrap().label(), "Claude Code");
+        assert_eq!(adapter("claude-desktop").unwrap().label(), "Claude Desktop");
+        assert!(matches!(adapter("vscode"), Err(ConfigError::InvalidInput(_))));
+        assert!(matches!(adapter(""), Err(ConfigError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_convert_goes_through_the_canonical_model() {
+        let mut sidecar = Sidecar::default();
+        let opencode = json!({ "type": "local", "command": ["npx", "srv"], "enabled": false });
+
+        let (claude, report) = convert(OPENCODE, &McpScope::User, CLAUDE, &McpScope::User, &mut sidecar, "srv", &opencode);
+        assert_eq!(claude, json!({ "command": "npx", "args": ["srv"], "type": "stdio" }));
+        assert_eq!(report.dropped, ["enabled"]);
+
+        let (back, _) = convert(CLAUDE, &McpScope::User, OPENCODE, &McpScope::User, &mut sidecar, "srv", &claude);
+        assert_eq!(back, opencode);
+
+        // Same tool on both sides: the canonical round trip keeps the entry
+        let (same, _) = convert(CLAUDE, &McpScope::User, CLAUDE, &McpScope::User, &mut Sidecar::default(), "srv", &claude);
+        assert_eq!(same, claude);
+    }
+
+    #[test]
+    fn test_claude_desktop_keeps_stdio_and_rejects_remote() {
+        let mut sidecar = Sidecar::default();
+        let opencode = json!({
+            "type": "local",
+            "command": ["npx", "fs"],
+            "environment": { "ROOT": "/data" },
+            "timeout": 5000,
+        });
+
+        let (desktop, report) = convert(OPENCODE, &McpScope::User, CLAUDE_DESKTOP, &McpScope::User, &mut sidecar, "fs", &opencode);
+        assert_eq!(desktop, json!({ "command": "npx", "args": ["fs"], "env": { "ROOT": "/data" } }));
+        assert_eq!(report.dropped, ["timeout"]);
+        assert!(CLAUDE_DESKTOP.validate("fs", &desktop).is_empty());
+
+        let remote = json!({ "type": "http", "url": "https://mcp.example.com/mcp" });
+        let (converted, report) = convert(CLAUDE, &McpScope::User, CLAUDE_DESKTOP, &McpScope::User, &mut sidecar, "api", &remote);
+        assert_eq!(
+            report.warnings,
+            ["Claude Desktop only runs stdio servers; the remote server at https://mcp.example.com/mcp cannot be synced to it"]
+        );
+        assert!(!CLAUDE_DESKTOP.validate("api", &converted).is_empty());
+
+        // References are written as they are, with a warning for each
+        let claude = json!({ "command": "${HOME}/bin/fs", "env": { "TOKEN": "${TOKEN:-none}" } });
+        let (desktop, report) = convert(CLAUDE, &McpScope::User, CLAUDE_DESKTOP, &McpScope::User, &mut sidecar, "refs", &claude);
+        assert_eq!(desktop, claude);
+        assert_eq!(
+            report.warnings,
+            [
+                "Claude Desktop does not expand variables: ${HOME} is kept as literal text",
+                "Claude Desktop does not expand variables: ${TOKEN:-none} is kept as literal text",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cursor_shares_one_definition_with_opencode_and_claude() {
+        let mut sidecar = Sidecar::default();
+        let opencode = json!({
+            "type": "remote",
+            "url": "https://mcp.example.com/sse",
+            "headers": { "Authorization": "Bearer {env:TOKEN}" },
+        });
+
+        let (cursor, report) = convert(OPENCODE, &McpScope::User, CURSOR, &McpScope::User, &mut sidecar, "api", &opencode);
+        assert_eq!(
+            cursor,
+            json!({ "url": "https://mcp.example.com/sse", "headers": { "Authorization": "Bearer ${env:TOKEN}" } })
+        );
+        assert!(report.warnings.is_empty());
+        assert!(CURSOR.validate("api", &cursor).is_empty());
+
+        let (claude, report) = convert(CURSOR, &McpScope::User, CLAUDE, &McpScope::User, &mut sidecar, "api", &cursor);
+        assert_eq!(
+            claude,
+            json!({ "type": "sse", "url": "https://mcp.example.com/sse", "headers": { "Authorization": "Bearer ${TOKEN}" } })
+        );
+        assert_eq!(report.inferred, ["type"]);
+
+        // Stdio entries keep env; Cursor-only fields survive the round trip
+        let cursor = json!({ "command": "npx", "args": ["fs"], "env": { "ROOT": "${env:HOME}" }, "envFile": ".env" });
+        let (claude, report) = convert(CURSOR, &McpScope::User, CLAUDE, &McpScope::User, &mut sidecar, "fs", &cursor);
+        assert_eq!(claude, json!({ "command": "npx", "args": ["fs"], "env": { "ROOT": "${HOME}" }, "type": "stdio" }));
+        assert_eq!(report.dropped, ["envFile"]);
+        assert_eq!(convert(CLAUDE, &McpScope::User, CURSOR, &McpScope::User, &mut sidecar, "fs", &claude).0, cursor);
+    }
+}
@@ -0,0 +1,39 @@
+//! Settings of OpenClaude-Tools itself, stored next to the backups in the
+//! app config directory.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::ConfigError;
+use crate::file_io::write_atomic;
+use crate::paths::PathOverrides;
+use crate::snapshot::SnapshotRetention;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AppSettings {
+    pub snapshot_retention: SnapshotRetention,
+    /// Config locations chosen by the user instead of the resolved defaults.
+    pub path_overrides: PathOverrides,
+    /// Project directories whose `.mcp.json` is managed.
+    pub projects: Vec<String>,
+    /// Settings written by newer versions of the app.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Load settings from `path`, falling back to defaults when the file is missing.
+pub fn load(path: &Path) -> Result<AppSettings, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| ConfigError::parse(Some(path), &e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(AppSettings::default()),
+        Err(e) => Err(ConfigError::read(path, e)),
+    }
+}
+
+pub fn save(path: &Path, settings: &AppSettings) -> Result<(), ConfigError> {
+    let content = serde_json::to_string_pretty(settings).map_err(ConfigError::internal)?;
+    write_atomic(path, content.as_bytes()).map_err(|e| ConfigError::write(path, e))
+}
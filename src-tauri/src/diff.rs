@@ -0,0 +1,90 @@
+//! Field-level differences between two MCP configs.
+//!
+//! Objects are compared key by key and arrays index by index, so a changed
+//! argument shows up as `args[1]` rather than as a whole new `args` array.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// One field that differs. `old` is missing for added fields, `new` for
+/// removed ones.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FieldDiff {
+    /// Location of the field, e.g. `env.TOKEN` or `args[0]`; empty for the
+    /// value itself.
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// Every field that differs between `old` and `new`, in document order.
+pub fn diff(old: &Value, new: &Value) -> Vec<FieldDiff> {
+    let mut diffs = Vec::new();
+    diff_at("", Some(old), Some(new), &mut diffs);
+    diffs
+}
+
+fn diff_at(path: &str, old: Option<&Value>, new: Option<&Value>, diffs: &mut Vec<FieldDiff>) {
+    match (old, new) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            for (key, value) in a {
+                diff_at(&join(path, key), Some(value), b.get(key), diffs);
+            }
+            for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                diff_at(&join(path, key), None, Some(value), diffs);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for i in 0..a.len().max(b.len()) {
+                diff_at(&format!("{}[{}]", path, i), a.get(i), b.get(i), diffs);
+            }
+        }
+        (a, b) if a == b => {}
+        (a, b) => diffs.push(FieldDiff {
+            path: path.to_string(),
+            old: a.cloned(),
+            new: b.cloned(),
+        }),
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_reports_nested_fields() {
+        let old = json!({ "command": "npx", "args": ["-y", "server@1"], "env": { "A": "1", "B": "2" } });
+        let new = json!({ "command": "npx", "args": ["-y", "server@2", "--verbose"], "env": { "A": "1" }, "type": "stdio" });
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                FieldDiff { path: "args[1]".into(), old: Some(json!("server@1")), new: Some(json!("server@2")) },
+                FieldDiff { path: "args[2]".into(), old: None, new: Some(json!("--verbose")) },
+                FieldDiff { path: "env.B".into(), old: Some(json!("2")), new: None },
+                FieldDiff { path: "type".into(), old: None, new: Some(json!("stdio")) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_of_equal_or_retyped_values() {
+        assert!(diff(&json!({ "a": [1, { "b": null }] }), &json!({ "a": [1, { "b": null }] })).is_empty());
+        assert_eq!(
+            diff(&json!({ "command": "npx" }), &json!({ "command": ["npx"] })),
+            vec![FieldDiff { path: "command".into(), old: Some(json!("npx")), new: Some(json!(["npx"])) }]
+        );
+    }
+}
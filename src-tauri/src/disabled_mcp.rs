@@ -0,0 +1,107 @@
+//! Claude Code MCP servers the user switched off.
+//!
+//! `~/.claude.json` has no per-server "disabled" flag, so disabling a user or
+//! local scope server moves its entry out of that file and into this store in
+//! the app config directory. Enabling it moves the stored entry back
+//! unchanged. Project `.mcp.json` servers are disabled through
+//! `disabledMcpjsonServers` instead and never end up here.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::ConfigError;
+use crate::file_io::write_atomic_private;
+use crate::McpScope;
+
+/// A server taken out of `~/.claude.json`, with the exact config it had there.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DisabledServer {
+    pub name: String,
+    pub scope: McpScope,
+    pub config: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DisabledStore {
+    pub servers: Vec<DisabledServer>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl DisabledStore {
+    pub fn get(&self, name: &str, scope: &McpScope) -> Option<&DisabledServer> {
+        self.servers
+            .iter()
+            .find(|s| s.name == name && &s.scope == scope)
+    }
+
+    /// Store `config` for `name`, replacing an older entry of the same scope.
+    pub fn insert(&mut self, name: &str, scope: &McpScope, config: serde_json::Value) {
+        self.remove(name, scope);
+        self.servers.push(DisabledServer {
+            name: name.to_string(),
+            scope: scope.clone(),
+            config,
+        });
+    }
+
+    pub fn remove(&mut self, name: &str, scope: &McpScope) -> Option<serde_json::Value> {
+        let index = self
+            .servers
+            .iter()
+            .position(|s| s.name == name && &s.scope == scope)?;
+        Some(self.servers.remove(index).config)
+    }
+}
+
+/// Load the store from `path`; a missing file is an empty store.
+pub fn load(path: &Path) -> Result<DisabledStore, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| ConfigError::parse(Some(path), &e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(DisabledStore::default()),
+        Err(e) => Err(ConfigError::read(path, e)),
+    }
+}
+
+pub fn save(path: &Path, store: &DisabledStore) -> Result<(), ConfigError> {
+    let content = serde_json::to_string_pretty(store).map_err(ConfigError::internal)?;
+    write_atomic_private(path, content.as_bytes()).map_err(|e| ConfigError::write(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_store_roundtrip_keeps_config_per_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("disabled-mcp.json");
+        let local = McpScope::Local {
+            path: "/work/app".to_string(),
+        };
+        let mut store = load(&path).unwrap();
+        store.insert("github", &McpScope::User, json!({ "command": "gh", "args": ["mcp"] }));
+        store.insert("github", &local, json!({ "type": "http", "url": "https://x" }));
+        save(&path, &store).unwrap();
+
+        let mut loaded = load(&path).unwrap();
+
+        assert_eq!(loaded.servers.len(), 2);
+        assert_eq!(
+            loaded.remove("github", &local),
+            Some(json!({ "type": "http", "url": "https://x" }))
+        );
+        assert_eq!(loaded.remove("github", &local), None);
+        assert!(loaded.get("github", &McpScope::User).is_some());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+}
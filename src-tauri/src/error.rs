@@ -0,0 +1,258 @@
+//! Structured errors returned by every Tauri command.
+//!
+//! Errors cross the IPC boundary as an object rather than a string:
+//!
+//! ```json
+//! { "code": "parse_error", "message": "...", "path": "/home/u/.claude.json", "line": 12, "column": 5 }
+//! ```
+//!
+//! `code` is stable and meant for the UI to branch on; `message` is for display.
+//! Validation failures also carry `errors`, one message per invalid field.
+
+use serde::Serialize;
+use std::io;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("File not found: {path}")]
+    FileNotFound { path: String },
+    #[error("Permission denied: {path}")]
+    PermissionDenied { path: String },
+    #[error("Failed to read {path}: {message}")]
+    ReadError { path: String, message: String },
+    #[error("{}", parse_error_message(.path, .message, .line, .column))]
+    ParseError {
+        path: Option<String>,
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    #[error("Failed to write {path}: {message}")]
+    WriteError { path: String, message: String },
+    #[error("Config file was changed by another program while saving: {path}")]
+    Conflict { path: String },
+    #[error("MCP '{name}' not found in {tool} config")]
+    McpNotFound { name: String, tool: String },
+    #[error("Invalid MCP config: {}", .errors.join("; "))]
+    ValidationError { errors: Vec<String> },
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+fn parse_error_message(
+    path: &Option<String>,
+    message: &str,
+    line: &Option<usize>,
+    column: &Option<usize>,
+) -> String {
+    let location = match (line, column) {
+        (Some(line), Some(column)) => format!(" at line {}, column {}", line, column),
+        _ => String::new(),
+    };
+    match path {
+        Some(path) => format!("Failed to parse {}{}: {}", path, location, message),
+        None => format!("Invalid JSON{}: {}", location, message),
+    }
+}
+
+impl ConfigError {
+    /// Stable machine-readable code for the frontend.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConfigError::FileNotFound { .. } => "file_not_found",
+            ConfigError::PermissionDenied { .. } => "permission_denied",
+            ConfigError::ReadError { .. } => "read_error",
+            ConfigError::ParseError { .. } => "parse_error",
+            ConfigError::WriteError { .. } => "write_error",
+            ConfigError::Conflict { .. } => "conflict",
+            ConfigError::McpNotFound { .. } => "mcp_not_found",
+            ConfigError::ValidationError { .. } => "validation_error",
+            ConfigError::InvalidInput(_) => "invalid_input",
+            ConfigError::Internal(_) => "internal",
+        }
+    }
+
+    /// The file the error is about, if any.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            ConfigError::FileNotFound { path }
+            | ConfigError::PermissionDenied { path }
+            | ConfigError::ReadError { path, .. }
+            | ConfigError::WriteError { path, .. }
+            | ConfigError::Conflict { path } => Some(path),
+            ConfigError::ParseError { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Error from reading `path`.
+    pub fn read(path: &Path, err: io::Error) -> Self {
+        let path = path.to_string_lossy().to_string();
+        match err.kind() {
+            io::ErrorKind::NotFound => ConfigError::FileNotFound { path },
+            io::ErrorKind::PermissionDenied => ConfigError::PermissionDenied { path },
+            _ => ConfigError::ReadError {
+                path,
+                message: err.to_string(),
+            },
+        }
+    }
+
+    /// Error from writing `path`.
+    pub fn write(path: &Path, err: io::Error) -> Self {
+        let path = path.to_string_lossy().to_string();
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => ConfigError::PermissionDenied { path },
+            _ => ConfigError::WriteError {
+                path,
+                message: err.to_string(),
+            },
+        }
+    }
+
+    /// Parse failure of `path` (or of user input when `path` is `None`),
+    /// keeping serde's source location.
+    pub fn parse(path: Option<&Path>, err: &serde_json::Error) -> Self {
+        // serde reports line 0 for errors that have no position in the text.
+        let (line, column) = if err.line() > 0 {
+            (Some(err.line()), Some(err.column()))
+        } else {
+            (None, None)
+        };
+        ConfigError::ParseError {
+            path: path.map(|p| p.to_string_lossy().to_string()),
+            message: strip_serde_location(&err.to_string()),
+            line,
+            column,
+        }
+    }
+
+    pub fn mcp_not_found(name: &str, tool: &str) -> Self {
+        ConfigError::McpNotFound {
+            name: name.to_string(),
+            tool: tool.to_string(),
+        }
+    }
+
+    pub fn internal(err: impl std::fmt::Display) -> Self {
+        ConfigError::Internal(err.to_string())
+    }
+}
+
+/// serde_json appends " at line X column Y"; the location is reported separately.
+fn strip_serde_location(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message.to_string(),
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorPayload<'a> {
+    code: &'a str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+    /// One message per invalid field, for `validation_error`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<&'a [String]>,
+}
+
+impl Serialize for ConfigError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let (line, column) = match self {
+            ConfigError::ParseError { line, column, .. } => (*line, *column),
+            _ => (None, None),
+        };
+        let errors = match self {
+            ConfigError::ValidationError { errors } => Some(errors.as_slice()),
+            _ => None,
+        };
+        ErrorPayload {
+            code: self.code(),
+            message: self.to_string(),
+            path: self.path(),
+            line,
+            column,
+            errors,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_serializes_location() {
+        let err = serde_json::from_str::<serde_json::Value>("{\n  \"a\": 1,\n  \"b\" 2\n}").unwrap_err();
+        let error = ConfigError::parse(Some(Path::new("/tmp/opencode.json")), &err);
+
+        let value = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(value["code"], "parse_error");
+        assert_eq!(value["path"], "/tmp/opencode.json");
+        assert_eq!(value["line"], 3);
+        assert_eq!(value["column"], 7);
+        assert_eq!(
+            value["message"],
+            "Failed to parse /tmp/opencode.json at line 3, column 7: expected `:`"
+        );
+    }
+
+    #[test]
+    fn test_io_errors_map_to_specific_codes() {
+        let path = Path::new("/tmp/.claude.json");
+
+        let missing = ConfigError::read(path, io::Error::from(io::ErrorKind::NotFound));
+        let denied = ConfigError::write(path, io::Error::from(io::ErrorKind::PermissionDenied));
+
+        assert_eq!(missing.code(), "file_not_found");
+        assert_eq!(denied.code(), "permission_denied");
+        assert_eq!(serde_json::to_value(&denied).unwrap()["path"], "/tmp/.claude.json");
+    }
+
+    #[test]
+    fn test_validation_error_lists_every_field() {
+        let error = ConfigError::ValidationError {
+            errors: vec![
+                "mcp.a.command: expected array of strings".to_string(),
+                "mcp.a.enabled: expected boolean".to_string(),
+            ],
+        };
+
+        let value = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(value["code"], "validation_error");
+        assert_eq!(value["errors"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            value["message"],
+            "Invalid MCP config: mcp.a.command: expected array of strings; mcp.a.enabled: expected boolean"
+        );
+    }
+
+    #[test]
+    fn test_errors_without_path_omit_optional_fields() {
+        let value = serde_json::to_value(ConfigError::mcp_not_found("github", "OpenCode")).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "code": "mcp_not_found",
+                "message": "MCP 'github' not found in OpenCode config"
+            })
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! Crash-safe file writing shared by every save path in the app.
+//!
+//! Config files are never written in place: the new content goes to a temp
+//! file in the same directory, is flushed to disk, and is then renamed over the
+//! original. A killed process therefore leaves either the old file or the new
+//! one, never a truncated mix of both.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Identity of a file's content at the time it was read.
+///
+/// Used to detect that another process (typically Claude Code itself) rewrote
+/// a config file between our load and our save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fingerprint {
+    Missing,
+    Content { len: u64, hash: u64 },
+}
+
+impl Fingerprint {
+    pub fn of(content: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        Fingerprint::Content {
+            len: content.len() as u64,
+            hash: hasher.finish(),
+        }
+    }
+
+    /// Fingerprint of the file as it is on disk right now.
+    pub fn current(path: &Path) -> io::Result<Self> {
+        match fs::read(path) {
+            Ok(content) => Ok(Self::of(&content)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Fingerprint::Missing),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Read a text file together with its fingerprint; `None` if it does not exist.
+pub fn read_with_fingerprint(path: &Path) -> io::Result<(Option<String>, Fingerprint)> {
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            let fingerprint = Fingerprint::of(content.as_bytes());
+            Ok((Some(content), fingerprint))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok((None, Fingerprint::Missing)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Result of a write guarded by a fingerprint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteOutcome {
+    Written,
+    /// The file no longer matches the expected fingerprint; nothing was written.
+    Conflict,
+}
+
+/// Atomically replace `path` with `contents`.
+///
+/// - Parent directories are created when missing.
+/// - If `path` is a symlink, the link target is replaced and the link is kept.
+/// - Permissions (and on Unix, ownership) of an existing file are carried over.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    write_atomic_inner(path, contents, None, false).map(|_| ())
+}
+
+/// Like `write_atomic`, for the app's own state files that only the user
+/// may read: on Unix the file always ends up with mode 0600.
+pub fn write_atomic_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    write_atomic_inner(path, contents, None, true).map(|_| ())
+}
+
+/// Like `write_atomic`, but only if the file still matches `expected`.
+///
+/// The check runs after the temp file is fully written, right before the
+/// rename, to keep the race window as small as possible.
+pub fn write_atomic_if_unchanged(
+    path: &Path,
+    contents: &[u8],
+    expected: &Fingerprint,
+) -> io::Result<WriteOutcome> {
+    write_atomic_inner(path, contents, Some(expected), false)
+}
+
+fn write_atomic_inner(
+    path: &Path,
+    contents: &[u8],
+    expected: Option<&Fingerprint>,
+    private: bool,
+) -> io::Result<WriteOutcome> {
+    let target = resolve_symlink(path)?;
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    fs::create_dir_all(&dir)?;
+
+    let existing = fs::metadata(&target).ok();
+    let (tmp_path, mut tmp_file) = create_temp_file(&dir, &target)?;
+
+    let result = (|| {
+        tmp_file.write_all(contents)?;
+        if let Some(metadata) = &existing {
+            fs::set_permissions(&tmp_path, metadata.permissions())?;
+            copy_ownership(&tmp_path, metadata);
+        }
+        if private {
+            make_private(&tmp_path)?;
+        }
+        tmp_file.sync_all()?;
+        drop(tmp_file);
+        if let Some(expected) = expected {
+            if Fingerprint::current(&target)? != *expected {
+                return Ok(WriteOutcome::Conflict);
+            }
+        }
+        fs::rename(&tmp_path, &target)?;
+        Ok(WriteOutcome::Written)
+    })();
+
+    match result {
+        Ok(WriteOutcome::Written) => {
+            sync_directory(&dir);
+            Ok(WriteOutcome::Written)
+        }
+        Ok(WriteOutcome::Conflict) => {
+            let _ = fs::remove_file(&tmp_path);
+            Ok(WriteOutcome::Conflict)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
+
+/// Follow a symlink so the rename replaces the file it points to.
+fn resolve_symlink(path: &Path) -> io::Result<PathBuf> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            let link = fs::read_link(path)?;
+            if link.is_absolute() {
+                Ok(link)
+            } else {
+                Ok(path.parent().unwrap_or_else(|| Path::new(".")).join(link))
+            }
+        }
+        _ => Ok(path.to_path_buf()),
+    }
+}
+
+fn create_temp_file(dir: &Path, target: &Path) -> io::Result<(PathBuf, File)> {
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "config".to_string());
+    let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+
+    for attempt in 0..16u32 {
+        let tmp_path = dir.join(format!(
+            ".{}.{}.{}.{}.tmp",
+            file_name,
+            std::process::id(),
+            nanos,
+            attempt
+        ));
+        match OpenOptions::new().write(true).create_new(true).open(&tmp_path) {
+            Ok(file) => return Ok((tmp_path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("Could not create a temp file next to {:?}", target),
+    ))
+}
+
+#[cfg(unix)]
+fn copy_ownership(tmp_path: &Path, original: &fs::Metadata) {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(current) = fs::metadata(tmp_path) else {
+        return;
+    };
+    if current.uid() == original.uid() && current.gid() == original.gid() {
+        return;
+    }
+    if let Err(e) = std::os::unix::fs::chown(tmp_path, Some(original.uid()), Some(original.gid())) {
+        log::warn!("Could not preserve ownership of {:?}: {}", tmp_path, e);
+    }
+}
+
+#[cfg(not(unix))]
+fn copy_ownership(_tmp_path: &Path, _original: &fs::Metadata) {}
+
+/// Make `path` readable and writable by its owner only.
+#[cfg(unix)]
+fn make_private(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn make_private(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Persist the rename itself; best effort, not every platform supports it.
+#[cfg(unix)]
+fn sync_directory(dir: &Path) {
+    if let Ok(handle) = File::open(dir) {
+        let _ = handle.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_directory(_dir: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_creates_file_and_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.json");
+
+        write_atomic(&path, b"{}").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_content_without_leftovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "old content that is longer than the new one").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let entries: Vec<_> = fs::read_dir(dir.path()).unwrap().flatten().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_write_atomic_if_unchanged_detects_modification() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "{\"a\":1}").unwrap();
+        let (_, fingerprint) = read_with_fingerprint(&path).unwrap();
+
+        fs::write(&path, "{\"a\":2}").unwrap();
+        let outcome = write_atomic_if_unchanged(&path, b"{\"a\":3}", &fingerprint).unwrap();
+
+        assert_eq!(outcome, WriteOutcome::Conflict);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\":2}");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_atomic_if_unchanged_detects_created_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let (content, fingerprint) = read_with_fingerprint(&path).unwrap();
+        assert!(content.is_none());
+
+        fs::write(&path, "{}").unwrap();
+        let outcome = write_atomic_if_unchanged(&path, b"[]", &fingerprint).unwrap();
+
+        assert_eq!(outcome, WriteOutcome::Conflict);
+    }
+
+    #[test]
+    fn test_write_atomic_if_unchanged_writes_when_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "{}").unwrap();
+        let (_, fingerprint) = read_with_fingerprint(&path).unwrap();
+
+        let outcome = write_atomic_if_unchanged(&path, b"[]", &fingerprint).unwrap();
+
+        assert_eq!(outcome, WriteOutcome::Written);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[]");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomic(&path, b"{\"a\":1}").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_private_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (new, existing) = (dir.path().join("new.json"), dir.path().join("existing.json"));
+        fs::write(&existing, "{}").unwrap();
+        fs::set_permissions(&existing, fs::Permissions::from_mode(0o644)).unwrap();
+
+        for path in [&new, &existing] {
+            write_atomic_private(path, b"{}").unwrap();
+            assert_eq!(fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_follows_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real.json");
+        let link = dir.path().join("link.json");
+        fs::write(&real, "{}").unwrap();
+        std::os::unix::fs::symlink("real.json", &link).unwrap();
+
+        write_atomic(&link, b"[1]").unwrap();
+
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_to_string(&real).unwrap(), "[1]");
+    }
+}
@@ -0,0 +1,164 @@
+//! Append-only journal of every MCP change the app makes (F08).
+//!
+//! Each line of the journal is one JSON [`HistoryEntry`] holding the entry as
+//! it was before and after the change, which is enough to undo it later.
+//! Lines are only ever appended; an undo is itself a new entry.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::error::ConfigError;
+use crate::McpScope;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryAction {
+    Sync,
+    Add,
+    Update,
+    Delete,
+    Undo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    pub id: String,
+    /// RFC 3339, local time.
+    pub timestamp: String,
+    pub action: HistoryAction,
+    /// Tool the config came from; the same as `target` except for syncs.
+    pub source: String,
+    /// Tool whose config was written.
+    pub target: String,
+    #[serde(default)]
+    pub scope: McpScope,
+    pub name: String,
+    /// The entry before the change; `None` when it did not exist.
+    pub before: Option<serde_json::Value>,
+    /// The entry after the change; `None` when it was deleted.
+    pub after: Option<serde_json::Value>,
+    /// For undo entries, the id of the entry that was undone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undoes: Option<String>,
+    /// The server was disabled, so the change was made where the tool keeps
+    /// disabled servers rather than in its config.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+impl HistoryEntry {
+    /// A new entry stamped with the current time.
+    pub fn new(
+        action: HistoryAction,
+        source: &str,
+        target: &str,
+        scope: &McpScope,
+        name: &str,
+        before: Option<serde_json::Value>,
+        after: Option<serde_json::Value>,
+    ) -> Self {
+        let now = chrono::Local::now();
+        HistoryEntry {
+            id: now
+                .timestamp_nanos_opt()
+                .unwrap_or_else(|| now.timestamp_millis())
+                .to_string(),
+            timestamp: now.to_rfc3339(),
+            action,
+            source: source.to_string(),
+            target: target.to_string(),
+            scope: scope.clone(),
+            name: name.to_string(),
+            before,
+            after,
+            undoes: None,
+            disabled: false,
+        }
+    }
+}
+
+/// Append `entry` to the journal at `path`, creating it if needed.
+pub fn append(path: &Path, entry: &HistoryEntry) -> Result<(), ConfigError> {
+    let mut line = serde_json::to_string(entry).map_err(ConfigError::internal)?;
+    line.push('\n');
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| ConfigError::write(dir, e))?;
+    }
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    // The journal holds full server configs, tokens included
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| ConfigError::write(path, e))
+}
+
+/// All journal entries, oldest first. Lines that do not parse (e.g. a write
+/// cut short) are logged and skipped.
+pub fn read(path: &Path) -> Result<Vec<HistoryEntry>, ConfigError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(ConfigError::read(path, e)),
+    };
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("Skipping unreadable history line in {:?}: {}", path, e);
+                None
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_journal_appends_and_skips_broken_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let add = HistoryEntry::new(
+            HistoryAction::Add,
+            "claude",
+            "claude",
+            &McpScope::User,
+            "github",
+            None,
+            Some(json!({ "command": "gh" })),
+        );
+        let sync = HistoryEntry::new(
+            HistoryAction::Sync,
+            "claude",
+            "opencode",
+            &McpScope::User,
+            "github",
+            None,
+            Some(json!({ "type": "local", "command": ["gh"] })),
+        );
+
+        append(&path, &add).unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "{ truncated\n").unwrap();
+        append(&path, &sync).unwrap();
+
+        assert_eq!(read(&path).unwrap(), vec![add, sync]);
+        assert!(read(&dir.path().join("missing.jsonl")).unwrap().is_empty());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+}
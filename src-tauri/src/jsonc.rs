@@ -0,0 +1,620 @@
+//! JSONC (JSON with comments) support for OpenCode config files.
+//!
+//! Parsing blanks out comments and trailing commas (keeping every newline, so
+//! serde's line/column still point into the original text) and hands the
+//! result to `serde_json`.
+//!
+//! Saving does not re-serialize the whole document. `update` diffs the value
+//! currently in the file against the new one and rewrites only the members
+//! that changed, leaving comments, key order and layout everywhere else alone.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Parse JSONC text into any deserializable type.
+pub fn parse<T: DeserializeOwned>(text: &str) -> serde_json::Result<T> {
+    serde_json::from_str(&strip(text))
+}
+
+/// Replace comments and trailing commas with whitespace.
+///
+/// Newlines inside block comments are kept so positions reported by
+/// `serde_json` stay valid for the original text.
+pub fn strip(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = skip_string(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'/') || bytes.get(i + 1) == Some(&b'*') => {
+                let end = skip_comment(bytes, i);
+                blank(&mut out[i..end]);
+                i = end;
+            }
+            b',' => {
+                let next = skip_trivia(bytes, i + 1);
+                if matches!(bytes.get(next), Some(b'}') | Some(b']')) {
+                    out[i] = b' ';
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    // Only ASCII bytes were replaced with ASCII spaces, so this stays valid UTF-8.
+    String::from_utf8(out).unwrap_or_else(|_| text.to_string())
+}
+
+/// Rewrite `text` so it holds `new_value`, touching only what changed.
+///
+/// The result is re-parsed and compared with `new_value`; any mismatch is
+/// reported as an error so callers can fall back to a plain pretty-print.
+pub fn update(text: &str, new_value: &Value) -> Result<String, String> {
+    let old_value: Value = parse(text).map_err(|e| e.to_string())?;
+    let root = Parser::new(text).parse_document()?;
+    let style = Style::detect(text);
+
+    let mut edits = Vec::new();
+    diff_node(text, &style, &root, &old_value, new_value, &mut edits);
+    let updated = apply_edits(text, edits);
+
+    let check: Value = parse(&updated).map_err(|e| format!("edited document is invalid: {}", e))?;
+    if &check != new_value {
+        return Err("edited document does not match the expected value".to_string());
+    }
+    Ok(updated)
+}
+
+// ============================================================================
+// Lexing helpers
+// ============================================================================
+
+fn blank(bytes: &mut [u8]) {
+    for b in bytes {
+        if *b != b'\n' && *b != b'\r' {
+            *b = b' ';
+        }
+    }
+}
+
+/// Return the index just past the string literal starting at `start`.
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Return the index just past the comment starting at `start`.
+fn skip_comment(bytes: &[u8], start: usize) -> usize {
+    if bytes.get(start + 1) == Some(&b'/') {
+        let mut i = start + 2;
+        while i < bytes.len() && bytes[i] != b'\n' {
+            i += 1;
+        }
+        i
+    } else {
+        let mut i = start + 2;
+        while i + 1 < bytes.len() {
+            if bytes[i] == b'*' && bytes[i + 1] == b'/' {
+                return i + 2;
+            }
+            i += 1;
+        }
+        bytes.len()
+    }
+}
+
+/// Skip whitespace and comments, returning the next significant index.
+fn skip_trivia(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'\t' | b'\n' | b'\r' => i += 1,
+            b'/' if bytes.get(i + 1) == Some(&b'/') || bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_comment(bytes, i);
+            }
+            _ => break,
+        }
+    }
+    i
+}
+
+// ============================================================================
+// Span tree
+// ============================================================================
+
+#[derive(Debug)]
+enum Node {
+    Object {
+        start: usize,
+        end: usize,
+        members: Vec<Member>,
+    },
+    Other {
+        start: usize,
+        end: usize,
+    },
+}
+
+impl Node {
+    fn span(&self) -> (usize, usize) {
+        match self {
+            Node::Object { start, end, .. } | Node::Other { start, end } => (*start, *end),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Member {
+    key: String,
+    key_start: usize,
+    value: Node,
+    /// Position of the comma following this member, if any.
+    comma: Option<usize>,
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            bytes: text.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn parse_document(mut self) -> Result<Node, String> {
+        self.pos = skip_trivia(self.bytes, self.pos);
+        let root = self.parse_value()?;
+        if !matches!(root, Node::Object { .. }) {
+            return Err("top-level value is not an object".to_string());
+        }
+        Ok(root)
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.pos = skip_trivia(self.bytes, self.pos);
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn parse_value(&mut self) -> Result<Node, String> {
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => {
+                let start = self.pos;
+                self.pos = skip_string(self.bytes, start);
+                Ok(Node::Other {
+                    start,
+                    end: self.pos,
+                })
+            }
+            Some(_) => {
+                let start = self.pos;
+                while self.pos < self.bytes.len()
+                    && !matches!(
+                        self.bytes[self.pos],
+                        b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r' | b'/'
+                    )
+                {
+                    self.pos += 1;
+                }
+                Ok(Node::Other {
+                    start,
+                    end: self.pos,
+                })
+            }
+            None => Err("unexpected end of document".to_string()),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Node, String> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut members: Vec<Member> = Vec::new();
+
+        loop {
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Node::Object {
+                        start,
+                        end: self.pos,
+                        members,
+                    });
+                }
+                Some(b',') => {
+                    let comma = self.pos;
+                    match members.last_mut() {
+                        Some(last) if last.comma.is_none() => last.comma = Some(comma),
+                        _ => return Err(format!("unexpected ',' at byte {}", comma)),
+                    }
+                    self.pos += 1;
+                }
+                Some(b'"') => {
+                    let key_start = self.pos;
+                    let key_end = skip_string(self.bytes, key_start);
+                    let key: String = serde_json::from_str(&self.text[key_start..key_end])
+                        .map_err(|e| format!("invalid key at byte {}: {}", key_start, e))?;
+                    self.pos = key_end;
+                    if self.peek() != Some(b':') {
+                        return Err(format!("expected ':' at byte {}", self.pos));
+                    }
+                    self.pos += 1;
+                    let value = self.parse_value()?;
+                    members.push(Member {
+                        key,
+                        key_start,
+                        value,
+                        comma: None,
+                    });
+                }
+                _ => return Err(format!("unexpected character at byte {}", self.pos)),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Node, String> {
+        let start = self.pos;
+        self.pos += 1;
+
+        loop {
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Node::Other {
+                        start,
+                        end: self.pos,
+                    });
+                }
+                Some(b',') => self.pos += 1,
+                Some(_) => {
+                    self.parse_value()?;
+                }
+                None => return Err("unterminated array".to_string()),
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Diff and edit
+// ============================================================================
+
+/// Indentation style of the document being edited.
+struct Style {
+    unit: String,
+    newline: &'static str,
+}
+
+impl Style {
+    fn detect(text: &str) -> Self {
+        let unit = text
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start_matches([' ', '\t']);
+                &line[..line.len() - trimmed.len()]
+            })
+            .find(|indent| !indent.is_empty())
+            .unwrap_or("  ")
+            .to_string();
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        Self { unit, newline }
+    }
+
+    /// Pretty-print `value` so that it can be placed on a line indented by `indent`.
+    fn render(&self, value: &Value, indent: &str) -> String {
+        let mut buf = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(self.unit.as_bytes());
+        let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+        serde::Serialize::serialize(value, &mut ser).expect("serializing a Value cannot fail");
+        let rendered = String::from_utf8(buf).expect("serde_json emits UTF-8");
+
+        let separator = format!("{}{}", self.newline, indent);
+        rendered.split('\n').collect::<Vec<_>>().join(&separator)
+    }
+
+    fn render_member(&self, key: &str, value: &Value, indent: &str) -> String {
+        format!(
+            "{}: {}",
+            Value::String(key.to_string()),
+            self.render(value, indent)
+        )
+    }
+}
+
+struct Edit {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+/// Whitespace from the start of the line up to `pos`, if nothing else precedes it.
+fn line_indent(text: &str, pos: usize) -> Option<&str> {
+    let line_start = text[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let prefix = &text[line_start..pos];
+    prefix
+        .chars()
+        .all(|c| c == ' ' || c == '\t')
+        .then_some(prefix)
+}
+
+/// Indentation of the line containing `pos`.
+fn containing_line_indent(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = &text[line_start..];
+    let trimmed = line.trim_start_matches([' ', '\t']);
+    &line[..line.len() - trimmed.len()]
+}
+
+fn diff_node(
+    text: &str,
+    style: &Style,
+    node: &Node,
+    old: &Value,
+    new: &Value,
+    edits: &mut Vec<Edit>,
+) {
+    if old == new {
+        return;
+    }
+
+    let (start, end) = node.span();
+    match (node, old.as_object(), new.as_object()) {
+        (Node::Object { members, .. }, Some(old_map), Some(new_map)) => {
+            diff_object(text, style, node, members, old_map, new_map, edits);
+        }
+        _ => {
+            let indent = containing_line_indent(text, start);
+            edits.push(Edit {
+                start,
+                end,
+                text: style.render(new, indent),
+            });
+        }
+    }
+}
+
+fn diff_object(
+    text: &str,
+    style: &Style,
+    node: &Node,
+    members: &[Member],
+    old_map: &serde_json::Map<String, Value>,
+    new_map: &serde_json::Map<String, Value>,
+    edits: &mut Vec<Edit>,
+) {
+    let (obj_start, obj_end) = node.span();
+    let obj_indent = containing_line_indent(text, obj_start).to_string();
+
+    // Members whose key appears more than once cannot be mapped reliably.
+    let has_duplicates = members
+        .iter()
+        .enumerate()
+        .any(|(i, m)| members[..i].iter().any(|o| o.key == m.key));
+    let added: Vec<(&String, &Value)> = new_map
+        .iter()
+        .filter(|(k, _)| !old_map.contains_key(*k))
+        .collect();
+    let kept: Vec<usize> = (0..members.len())
+        .filter(|&i| new_map.contains_key(&members[i].key))
+        .collect();
+
+    if has_duplicates || kept.is_empty() {
+        edits.push(Edit {
+            start: obj_start,
+            end: obj_end,
+            text: if new_map.is_empty() {
+                "{}".to_string()
+            } else {
+                style.render(&Value::Object(new_map.clone()), &obj_indent)
+            },
+        });
+        return;
+    }
+
+    // Changed members.
+    for &i in &kept {
+        let member = &members[i];
+        if let (Some(old), Some(new)) = (old_map.get(&member.key), new_map.get(&member.key)) {
+            diff_node(text, style, &member.value, old, new, edits);
+        }
+    }
+
+    // Removed members: each takes the trivia before it plus its own comma.
+    for (i, member) in members.iter().enumerate() {
+        if new_map.contains_key(&member.key) {
+            continue;
+        }
+        let seg_start = if i == 0 {
+            obj_start + 1
+        } else {
+            members[i - 1].comma.map(|c| c + 1).unwrap_or(members[i - 1].value.span().1)
+        };
+        let seg_end = member.comma.map(|c| c + 1).unwrap_or(member.value.span().1);
+        edits.push(Edit {
+            start: seg_start,
+            end: seg_end,
+            text: String::new(),
+        });
+    }
+
+    let last = &members[*kept.last().expect("kept is not empty")];
+    let original_last = members.last().expect("members is not empty");
+    let trailing_style = original_last.comma.is_some();
+    let mut last_has_comma = last.comma.is_some();
+
+    // The new last member must not keep a separator that now dangles.
+    if let Some(comma) = last.comma {
+        if !trailing_style {
+            edits.push(Edit {
+                start: comma,
+                end: comma + 1,
+                text: String::new(),
+            });
+            last_has_comma = false;
+        }
+    }
+
+    if added.is_empty() {
+        return;
+    }
+
+    let indent = line_indent(text, last.key_start)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| format!("{}{}", obj_indent, style.unit));
+    let rendered: Vec<String> = added
+        .iter()
+        .map(|(k, v)| style.render_member(k, v, &indent))
+        .collect();
+    let separator = format!(",{}{}", style.newline, indent);
+
+    let edit = if last_has_comma {
+        let comma = last.comma.expect("checked above");
+        Edit {
+            start: comma + 1,
+            end: comma + 1,
+            text: format!("{}{}{},", style.newline, indent, rendered.join(&separator)),
+        }
+    } else {
+        let pos = last.value.span().1;
+        Edit {
+            start: pos,
+            end: pos,
+            text: format!("{}{}", separator, rendered.join(&separator)),
+        }
+    };
+    edits.push(edit);
+}
+
+fn apply_edits(text: &str, mut edits: Vec<Edit>) -> String {
+    // Apply back to front; for equal starts, the wider range goes first so an
+    // insertion lands in front of a removal at the same position.
+    edits.sort_by(|a, b| b.start.cmp(&a.start).then(b.end.cmp(&a.end)));
+    let mut out = text.to_string();
+    for edit in edits {
+        out.replace_range(edit.start..edit.end, &edit.text);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DOCUMENT: &str = r#"{
+  // Theme picked by hand
+  "theme": "tokyonight",
+  /* MCP servers */
+  "mcp": {
+    // GitHub access for the agent
+    "github": {
+      "type": "local",
+      "command": ["npx", "-y", "@modelcontextprotocol/server-github"], // pinned
+      "enabled": true,
+    },
+    "fetch": { "type": "remote", "url": "https://fetch.example/mcp" },
+  },
+}
+"#;
+
+    #[test]
+    fn test_parse_accepts_comments_and_trailing_commas() {
+        let value: Value = parse(DOCUMENT).unwrap();
+
+        assert_eq!(value["theme"], "tokyonight");
+        assert_eq!(value["mcp"]["github"]["enabled"], true);
+        assert_eq!(value["mcp"]["fetch"]["url"], "https://fetch.example/mcp");
+    }
+
+    #[test]
+    fn test_parse_keeps_comment_markers_inside_strings() {
+        let value: Value = parse(r#"{ "url": "https://a.example//x", "glob": "/* not a comment */", }"#).unwrap();
+
+        assert_eq!(value["url"], "https://a.example//x");
+        assert_eq!(value["glob"], "/* not a comment */");
+    }
+
+    #[test]
+    fn test_parse_error_points_into_original_text() {
+        let text = "{\n  // comment\n  /* block\n  comment */\n  \"a\": 1 \"b\": 2\n}";
+        let err = parse::<Value>(text).unwrap_err();
+
+        assert_eq!(err.line(), 5);
+    }
+
+    #[test]
+    fn test_update_changes_one_member_and_keeps_comments() {
+        let mut value: Value = parse(DOCUMENT).unwrap();
+        value["mcp"]["github"]["enabled"] = json!(false);
+
+        let updated = update(DOCUMENT, &value).unwrap();
+
+        assert_eq!(updated, DOCUMENT.replace("\"enabled\": true", "\"enabled\": false"));
+    }
+
+    #[test]
+    fn test_update_inserts_member_with_trailing_comma_style() {
+        let mut value: Value = parse(DOCUMENT).unwrap();
+        value["mcp"]["docs"] = json!({ "type": "remote", "url": "https://docs.example" });
+
+        let updated = update(DOCUMENT, &value).unwrap();
+
+        assert!(updated.contains("// GitHub access for the agent"));
+        assert!(updated.contains("// pinned"));
+        assert!(updated.contains(
+            "    \"docs\": {\n      \"type\": \"remote\",\n      \"url\": \"https://docs.example\"\n    },\n  },"
+        ));
+        assert_eq!(parse::<Value>(&updated).unwrap(), value);
+    }
+
+    #[test]
+    fn test_update_removes_member_with_its_comment() {
+        let mut value: Value = parse(DOCUMENT).unwrap();
+        value["mcp"].as_object_mut().unwrap().remove("github");
+
+        let updated = update(DOCUMENT, &value).unwrap();
+
+        assert!(!updated.contains("GitHub access"));
+        assert!(updated.contains("// Theme picked by hand"));
+        assert!(updated.contains("/* MCP servers */"));
+        assert_eq!(parse::<Value>(&updated).unwrap(), value);
+    }
+
+    #[test]
+    fn test_update_strict_json_stays_strict() {
+        let text = "{\n    \"mcp\": {\n        \"a\": {\"command\": [\"a\"]},\n        \"b\": {\"command\": [\"b\"]}\n    }\n}\n";
+        let mut value: Value = serde_json::from_str(text).unwrap();
+        value["mcp"].as_object_mut().unwrap().remove("b");
+        value["mcp"]["c"] = json!({ "command": ["c"] });
+
+        let updated = update(text, &value).unwrap();
+
+        let strict: Value = serde_json::from_str(&updated).unwrap();
+        assert_eq!(strict, value);
+        assert!(updated.contains("        \"c\": {\n            \"command\": [\n"));
+    }
+
+    #[test]
+    fn test_update_adds_top_level_key_to_empty_object() {
+        let value = json!({ "mcp": { "a": { "enabled": true } } });
+
+        let updated = update("{}", &value).unwrap();
+
+        assert_eq!(parse::<Value>(&updated).unwrap(), value);
+    }
+}
@@ -1,29 +1,34 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::path::PathBuf;
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum ConfigError {
-    #[error("File not found: {0}")]
-    FileNotFound(String),
-    #[error("Parse error: {0}")]
-    ParseError(String),
-    #[error("Write error: {0}")]
-    WriteError(String),
-    #[error("Permission denied: {0}")]
-    PermissionDenied(String),
-}
-
-impl Serialize for ConfigError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
-    }
-}
+use std::path::{Path, PathBuf};
+
+mod adapter;
+mod app_settings;
+mod diff;
+mod disabled_mcp;
+mod error;
+mod file_io;
+mod history;
+mod jsonc;
+mod merge;
+mod paths;
+mod plugins;
+mod refs;
+mod sidecar;
+mod snapshot;
+mod validate;
+
+pub use error::ConfigError;
+
+use adapter::ToolAdapter;
+use app_settings::AppSettings;
+use diff::FieldDiff;
+use file_io::{write_atomic, WriteOutcome};
+use history::{HistoryAction, HistoryEntry};
+use paths::{Environment, PathResolver, ResolvedPath};
+use snapshot::{SnapshotInfo, SnapshotRetention};
 
 // ============================================================================
 // MCP Types - Simplified: name + raw JSON config
@@ -34,9 +39,44 @@ impl Serialize for ConfigError {
 pub struct MCPItem {
     pub name: String,
     pub config: String, // Raw JSON string
-    pub source: String, // "opencode" or "claude"
+    pub source: String, // Tool adapter id: "opencode", "claude", ...
     pub enabled: bool,
     pub description: Option<String>,
+    #[serde(default)]
+    pub scope: McpScope,
+    /// Owned by another program (e.g. a Claude Code plugin); can be copied
+    /// elsewhere but never edited in place.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Where a Claude Code MCP server is defined. OpenCode and Claude Desktop
+/// servers are always `user`; Cursor has `user` (`~/.cursor/mcp.json`) and
+/// `project` (`<path>/.cursor/mcp.json`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum McpScope {
+    /// Top-level `mcpServers` in ~/.claude.json, available in every project.
+    #[default]
+    User,
+    /// `projects["<path>"].mcpServers` in ~/.claude.json, only for that project.
+    Local { path: String },
+    /// `<path>/.mcp.json`, shared with everyone working on the project.
+    Project { path: String },
+    /// Shipped by an installed Claude Code plugin in the `.mcp.json` at `path`. Read-only.
+    Plugin { plugin: String, path: String },
+}
+
+impl McpScope {
+    /// Name of the Claude config the scope lives in, for messages.
+    fn claude_label(&self) -> String {
+        match self {
+            McpScope::User => "Claude".to_string(),
+            McpScope::Local { path } => format!("Claude project {}", path),
+            McpScope::Project { path } => format!("{}/.mcp.json", path.trim_end_matches('/')),
+            McpScope::Plugin { plugin, .. } => format!("Claude plugin {}", plugin),
+        }
+    }
 }
 
 /// MCP list response
@@ -44,21 +84,41 @@ pub struct MCPItem {
 pub struct MCPList {
     pub opencode: Vec<MCPItem>,
     pub claude: Vec<MCPItem>,
+    /// Servers from the `.mcp.json` of registered project directories.
+    pub project: Vec<MCPItem>,
+    /// Read-only servers contributed by installed Claude Code plugins.
+    pub plugin: Vec<MCPItem>,
+    /// Servers of every other registered tool, keyed by its id.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Vec<MCPItem>>,
 }
 
+/// MCP servers by name, in file order.
+type McpServers = serde_json::Map<String, serde_json::Value>;
+
 // ============================================================================
 // OpenCode Configuration (for internal use only)
 // ============================================================================
 
+/// OpenCode document model.
+///
+/// Only the keys this app edits are typed; everything else (`theme`, `agent`,
+/// `command`, `instructions`, `permission`, `keybinds`, `lsp`, `formatter`, ...)
+/// is kept in `other` so a load/save cycle never drops user configuration.
+/// MCP entries are stored as raw values, so unknown fields inside each entry
+/// survive as well.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct OpenCodeConfig {
-    #[serde(rename = "$schema")]
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
     pub schema: Option<String>,
-    #[serde(default)]
-    pub mcp: HashMap<String, serde_json::Value>,
-    #[serde(rename = "provider")]
-    pub providers: Option<HashMap<String, serde_json::Value>>,
-    pub plugin: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub mcp: serde_json::Map<String, serde_json::Value>,
+    #[serde(rename = "provider", default, skip_serializing_if = "Option::is_none")]
+    pub providers: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<Vec<serde_json::Value>>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
 // ============================================================================
@@ -67,12 +127,147 @@ pub struct OpenCodeConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ClaudeConfig {
-    pub env: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, rename = "mcpServers")]
+    pub mcp_servers: McpServers,
+    /// Per-project state, keyed by absolute project path, in file order.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub projects: IndexMap<String, ClaudeProject>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// One entry of `projects` in ~/.claude.json. Only the local-scope MCP
+/// servers are modelled; history, trust flags etc. are kept as they are.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ClaudeProject {
+    #[serde(default, rename = "mcpServers")]
+    pub mcp_servers: McpServers,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ClaudeConfig {
+    /// Servers of `scope`; `None` when the project is not in the file.
+    /// `.mcp.json` servers live in their own file and are never here.
+    fn servers_mut(&mut self, scope: &McpScope) -> Option<&mut McpServers> {
+        match scope {
+            McpScope::User => Some(&mut self.mcp_servers),
+            McpScope::Local { path } => self.projects.get_mut(path).map(|p| &mut p.mcp_servers),
+            McpScope::Project { .. } | McpScope::Plugin { .. } => None,
+        }
+    }
+
+    /// Servers of `scope`, adding the project entry when it does not exist yet.
+    fn servers_entry(&mut self, scope: &McpScope) -> Option<&mut McpServers> {
+        match scope {
+            McpScope::User => Some(&mut self.mcp_servers),
+            McpScope::Local { path } => {
+                Some(&mut self.projects.entry(path.clone()).or_default().mcp_servers)
+            }
+            McpScope::Project { .. } | McpScope::Plugin { .. } => None,
+        }
+    }
+}
+
+// ============================================================================
+// Claude Settings Files
+// ============================================================================
+
+/// Which Claude Code settings file: `~/.claude/settings.json` (user),
+/// `<project>/.claude/settings.json` (project, checked in) or
+/// `<project>/.claude/settings.local.json` (local, git-ignored).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsScope {
+    User,
+    Project,
+    Local,
+}
+
+/// Content of a Claude Code settings file. Only the keys the app works with
+/// are typed; everything else is kept as-is in `other`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<ClaudePermissions>,
+    /// Event name → matcher/hook list; passed through untouched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Approve every server in the project's `.mcp.json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_all_project_mcp_servers: Option<bool>,
+    /// `.mcp.json` servers the user approved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled_mcpjson_servers: Option<Vec<String>>,
+    /// `.mcp.json` servers the user rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_mcpjson_servers: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudePermissions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ask: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deny: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_directories: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_mode: Option<String>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A settings file as returned to the frontend, with where it lives.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeSettingsFile {
+    pub scope: SettingsScope,
+    pub path: String,
+    pub exists: bool,
+    pub settings: ClaudeSettings,
+}
+
+// ============================================================================
+// Project .mcp.json Configuration
+// ============================================================================
+
+/// A project's `.mcp.json`, checked into the repository next to the code.
+/// Cursor's `mcp.json` files have the same shape and use it too.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectMcpConfig {
+    #[serde(default, rename = "mcpServers")]
+    pub mcp_servers: McpServers,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+// ============================================================================
+// Claude Desktop Configuration
+// ============================================================================
+
+/// `claude_desktop_config.json`. Claude Desktop only runs stdio servers, so
+/// its `mcpServers` entries are `command`/`args`/`env` without a `type`;
+/// window and preference settings are kept in `other`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ClaudeDesktopConfig {
     #[serde(default, rename = "mcpServers")]
-    pub mcp_servers: HashMap<String, serde_json::Value>,
+    pub mcp_servers: McpServers,
     #[serde(flatten)]
-    pub other: HashMap<String, serde_json::Value>,
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
 // ============================================================================
@@ -106,19 +301,244 @@ pub struct OhMyOpenCodeConfig {
 // Sync Types
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SyncPreview {
-    pub opencode: SyncDirectionPreview,
-    pub claude: SyncDirectionPreview,
+/// Two tools by adapter id. One-way syncs copy `from` into `to`; for
+/// bidirectional syncs and comparisons the order only decides which tool is
+/// reported first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncDirection {
+    pub from: String,
+    pub to: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+impl SyncDirection {
+    /// The adapters of both tools, which must be two different ones.
+    fn adapters(&self) -> Result<ToolPair, ConfigError> {
+        if self.from == self.to {
+            return Err(ConfigError::InvalidInput(format!(
+                "Cannot sync {} with itself",
+                self.from
+            )));
+        }
+        Ok(ToolPair::new(adapter::adapter(&self.from)?, adapter::adapter(&self.to)?))
+    }
+}
+
+/// The source and target adapters of a sync.
+#[derive(Clone, Copy)]
+struct ToolPair {
+    from: &'static dyn ToolAdapter,
+    to: &'static dyn ToolAdapter,
+}
+
+impl ToolPair {
+    fn new(from: &'static dyn ToolAdapter, to: &'static dyn ToolAdapter) -> Self {
+        ToolPair { from, to }
+    }
+
+    fn reversed(self) -> Self {
+        ToolPair::new(self.to, self.from)
+    }
+
+    /// The pair in `adapter::ADAPTERS` order, so that both directions share
+    /// one merge base, and whether that reversed it.
+    fn ordered(self) -> (Self, bool) {
+        let position = |tool: &dyn ToolAdapter| adapter::ADAPTERS.iter().position(|a| a.id() == tool.id());
+        if position(self.from) > position(self.to) {
+            (self.reversed(), true)
+        } else {
+            (self, false)
+        }
+    }
+
+    /// Key of the pair's merge base.
+    fn base_key(self) -> String {
+        let (pair, _) = self.ordered();
+        merge::pair_key(pair.from.id(), pair.to.id())
+    }
+
+    /// Convert source entry `name` to the target tool's format, restoring
+    /// what the conversion cannot carry from `sidecar`.
+    fn convert(
+        self,
+        sidecar: &mut sidecar::Sidecar,
+        name: &str,
+        config: &serde_json::Value,
+    ) -> serde_json::Value {
+        self.convert_with_report(sidecar, name, config).0
+    }
+
+    /// Like `convert`, also reporting what happened to the fields.
+    fn convert_with_report(
+        self,
+        sidecar: &mut sidecar::Sidecar,
+        name: &str,
+        config: &serde_json::Value,
+    ) -> (serde_json::Value, ConversionReport) {
+        let user = &McpScope::User;
+        adapter::convert(self.from, user, self.to, user, sidecar, name, config)
+    }
+
+    /// Check a converted entry against what the target tool accepts.
+    fn validate(self, name: &str, config: &serde_json::Value) -> Vec<String> {
+        self.to.validate(name, config)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
 pub struct SyncDirectionPreview {
+    /// Servers missing from the target.
     pub added: Vec<String>,
-    pub updated: Vec<String>,
+    /// Servers whose converted config differs from the target's.
+    pub updated: Vec<SyncUpdate>,
+    /// Target servers the source does not have.
     pub removed: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncUpdate {
+    pub name: String,
+    /// Target config (old) versus converted source config (new).
+    pub diff: Vec<FieldDiff>,
+}
+
+/// How a user scope server of one name compares across the two tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ComparisonStatus {
+    Identical,
+    Differs,
+    OnlyInFrom,
+    OnlyInTo,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerComparison {
+    pub name: String,
+    pub status: ComparisonStatus,
+    /// Differing fields of the normalized configs, named as in Claude Code;
+    /// `old` holds the `from` tool's value and `new` the `to` tool's.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldDiff>,
+}
+
+/// What `sync_all` does with a server the target already has in another form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncStrategy {
+    /// Only copy servers the target lacks (SPEC F05).
+    #[default]
+    OnlyMissing,
+    /// Replace the target's version.
+    Overwrite,
+    /// Leave differing servers alone and report them as conflicts.
+    SkipOnConflict,
+    /// Replace the target's version when the source's changed later, judged
+    /// per server (see `Recency`); undecidable servers are conflicts.
+    PreferNewer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncAction {
+    Added,
+    Updated,
+    Unchanged,
+    Skipped,
+    Conflict,
+    /// The converted config would not load in the target tool.
+    Invalid,
+}
+
+/// What converting one entry to the other tool's format did to its fields.
+#[derive(Debug, Clone, Serialize, Default, PartialEq)]
+pub struct ConversionReport {
+    /// Source fields the target format has no place for.
+    pub dropped: Vec<String>,
+    /// Source fields written under another name.
+    pub renamed: Vec<FieldRename>,
+    /// Target fields the conversion had to guess or fill in.
+    pub inferred: Vec<String>,
+    /// Target fields taken back from the last conversion (the sidecar)
+    /// instead of being converted.
+    pub restored: Vec<String>,
+    /// Variable references the target cannot express.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FieldRename {
+    pub from: String,
+    pub to: String,
+}
+
+impl ConversionReport {
+    fn is_empty(&self) -> bool {
+        *self == ConversionReport::default()
+    }
+
+    /// Add the findings of a further conversion step.
+    fn append(&mut self, other: ConversionReport) {
+        self.dropped.extend(other.dropped);
+        self.renamed.extend(other.renamed);
+        self.inferred.extend(other.inferred);
+        self.restored.extend(other.restored);
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Record the fields of `converted` that differ from the plain
+    /// conversion `plain`, i.e. that the sidecar put back.
+    fn note_restored(&mut self, plain: &serde_json::Value, converted: &serde_json::Value) {
+        let Some(fields) = converted.as_object() else {
+            return;
+        };
+        for (key, value) in fields {
+            if plain.get(key) != Some(value) {
+                self.inferred.retain(|field| field != key);
+                self.restored.push(key.clone());
+            }
+        }
+    }
+
+    fn rename(&mut self, from: &str, to: &str) {
+        self.renamed.push(FieldRename {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncItemResult {
+    pub name: String,
+    pub action: SyncAction,
+    /// Target config versus converted source config, for updates and conflicts.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diff: Vec<FieldDiff>,
+    /// For added and updated servers, what the conversion changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<ConversionReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BidirectionalSyncReport {
+    /// Adapter ids of the tools the items call `left` and `right`.
+    pub left: String,
+    pub right: String,
+    pub dry_run: bool,
+    pub items: Vec<merge::MergeItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncReport {
+    pub direction: SyncDirection,
+    pub strategy: SyncStrategy,
+    /// Nothing was written; `items` tells what would have happened.
+    pub dry_run: bool,
+    pub items: Vec<SyncItemResult>,
+}
+
 // ============================================================================
 // Backup Types
 // ============================================================================
@@ -136,6 +556,129 @@ pub struct BackupData {
 // MCP Config Format Conversion Functions
 // ============================================================================
 
+/// How a client talks to an MCP server.
+///
+/// Claude Code spells it out in `type` (`stdio`, `sse`, `http`); OpenCode only
+/// tells `local` from `remote`, so for remote servers the transport comes from
+/// an explicit `transport` field when there is one and otherwise from the URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum McpTransport {
+    Stdio,
+    Sse,
+    StreamableHttp,
+}
+
+impl McpTransport {
+    /// Transport of a Claude Code entry; `None` when it has neither `type`,
+    /// `url` nor `command`.
+    fn of_claude(config: &serde_json::Value) -> Option<Self> {
+        match config.get("type").and_then(|t| t.as_str()) {
+            Some("stdio") => Some(McpTransport::Stdio),
+            Some("sse") => Some(McpTransport::Sse),
+            Some("http" | "streamable-http") => Some(McpTransport::StreamableHttp),
+            _ => Self::infer(config),
+        }
+    }
+
+    /// Transport of an OpenCode entry.
+    fn of_opencode(config: &serde_json::Value) -> Option<Self> {
+        let explicit = match config.get("transport").and_then(|t| t.as_str()) {
+            Some("sse") => Some(McpTransport::Sse),
+            Some("http" | "streamable-http") => Some(McpTransport::StreamableHttp),
+            _ => None,
+        };
+        match config.get("type").and_then(|t| t.as_str()) {
+            Some("local") => Some(McpTransport::Stdio),
+            Some("remote") => explicit.or_else(|| Some(Self::from_url(config.get("url")))),
+            _ => explicit.or_else(|| Self::infer(config)),
+        }
+    }
+
+    fn infer(config: &serde_json::Value) -> Option<Self> {
+        if config.get("url").is_some() {
+            Some(Self::from_url(config.get("url")))
+        } else if config.get("command").is_some() {
+            Some(McpTransport::Stdio)
+        } else {
+            None
+        }
+    }
+
+    /// SSE endpoints conventionally end in `/sse`; anything else is taken to
+    /// be streamable HTTP, the current MCP remote transport.
+    fn from_url(url: Option<&serde_json::Value>) -> Self {
+        let path = url
+            .and_then(|u| u.as_str())
+            .map(|u| u.split(['?', '#']).next().unwrap_or_default().trim_end_matches('/'))
+            .unwrap_or_default();
+        if path.ends_with("/sse") {
+            McpTransport::Sse
+        } else {
+            McpTransport::StreamableHttp
+        }
+    }
+
+    fn claude_type(self) -> &'static str {
+        match self {
+            McpTransport::Stdio => "stdio",
+            McpTransport::Sse => "sse",
+            McpTransport::StreamableHttp => "http",
+        }
+    }
+
+    fn opencode_type(self) -> &'static str {
+        match self {
+            McpTransport::Stdio => "local",
+            McpTransport::Sse | McpTransport::StreamableHttp => "remote",
+        }
+    }
+}
+
+/// Fields of each format whose strings may hold variable references.
+/// Cursor entries use the Claude Code field names.
+const OPENCODE_REF_FIELDS: [&str; 4] = ["command", "environment", "url", "headers"];
+const CLAUDE_REF_FIELDS: [&str; 5] = ["command", "args", "env", "url", "headers"];
+
+/// Rewrite the variable references in `fields` of `config` from `from`'s
+/// syntax to `to`'s.
+fn translate_refs(
+    config: &mut serde_json::Map<String, serde_json::Value>,
+    fields: &[&str],
+    from: refs::Syntax,
+    to: refs::Syntax,
+    warnings: &mut Vec<String>,
+) {
+    for field in fields {
+        if let Some(value) = config.get_mut(*field) {
+            *value = refs::translate(value, from, to, warnings);
+        }
+    }
+}
+
+/// Source fields outside `mapped`, which the conversion leaves behind.
+fn dropped_fields(config: &serde_json::Value, mapped: &[&str]) -> Vec<String> {
+    config
+        .as_object()
+        .map(|config| {
+            config
+                .keys()
+                .filter(|key| !mapped.contains(&key.as_str()))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+fn convert_opencode_to_claude(config: &serde_json::Value) -> serde_json::Value {
+    convert_opencode_to_claude_with_report(config).0
+}
+
+#[cfg(test)]
+fn convert_claude_to_opencode(config: &serde_json::Value) -> serde_json::Value {
+    convert_claude_to_opencode_with_report(config).0
+}
+
 /// Convert OpenCode MCP config format to Claude Code format
 ///
 /// OpenCode format:
@@ -152,10 +695,16 @@ pub struct BackupData {
 ///   "command": "npx",              // string or array
 ///   "args": ["-y", "pkg"],         // separate args
 ///   "env": { "KEY": "value" }
-///   // no enabled, description, type fields
+///   // no enabled, description fields
 /// }
-fn convert_opencode_to_claude(config: &serde_json::Value) -> serde_json::Value {
+fn convert_opencode_to_claude_with_report(
+    config: &serde_json::Value,
+) -> (serde_json::Value, ConversionReport) {
     let mut result = serde_json::Map::new();
+    let mut report = ConversionReport {
+        dropped: dropped_fields(config, adapter::OPENCODE.mapped_fields()),
+        ..Default::default()
+    };
     
     // Handle command conversion: array -> command + args
     if let Some(cmd) = config.get("command") {
@@ -169,6 +718,7 @@ fn convert_opencode_to_claude(config: &serde_json::Value) -> serde_json::Value {
                         "args".to_string(),
                         serde_json::Value::Array(cmd_array[1..].to_vec()),
                     );
+                    report.rename("command[1..]", "args");
                 }
             }
         } else {
@@ -180,6 +730,7 @@ fn convert_opencode_to_claude(config: &serde_json::Value) -> serde_json::Value {
     // Convert environment -> env
     if let Some(env) = config.get("environment") {
         result.insert("env".to_string(), env.clone());
+        report.rename("environment", "env");
     }
     
     // Copy common fields that both formats support
@@ -189,19 +740,31 @@ fn convert_opencode_to_claude(config: &serde_json::Value) -> serde_json::Value {
         }
     }
     
-    // Add type field based on config content (Claude Code requires this)
-    // - Has "url" -> type: "http" (remote MCP)
-    // - Has "command" -> type: "stdio" (local MCP)
-    if config.get("url").is_some() {
-        result.insert("type".to_string(), serde_json::json!("http"));
-    } else if config.get("command").is_some() {
-        result.insert("type".to_string(), serde_json::json!("stdio"));
+    // Add type field based on the transport (Claude Code requires this)
+    // - local -> type: "stdio"
+    // - remote -> type: "sse" or "http", see McpTransport
+    if let Some(transport) = McpTransport::of_opencode(config) {
+        result.insert("type".to_string(), serde_json::json!(transport.claude_type()));
+        // Only stdio is implied by OpenCode's type; remote transports come
+        // from an explicit `transport` field or are guessed from the URL
+        if transport != McpTransport::Stdio && config.get("transport").is_none() {
+            report.inferred.push("type".to_string());
+        }
     }
     
-    // Note: We intentionally skip "enabled", "description"
-    // as they are OpenCode-specific and not supported by Claude Code
-    
-    serde_json::Value::Object(result)
+    // Note: "enabled", "description" are OpenCode-specific and not
+    // supported by Claude Code; they end up in report.dropped
+
+    // {env:VAR} -> ${VAR}
+    translate_refs(
+        &mut result,
+        &CLAUDE_REF_FIELDS,
+        refs::Syntax::Opencode,
+        refs::Syntax::Claude,
+        &mut report.warnings,
+    );
+
+    (serde_json::Value::Object(result), report)
 }
 
 /// Convert Claude Code MCP config format to OpenCode format
@@ -220,19 +783,26 @@ fn convert_opencode_to_claude(config: &serde_json::Value) -> serde_json::Value {
 ///   "environment": { "KEY": "value" },
 ///   "enabled": true
 /// }
-fn convert_claude_to_opencode(config: &serde_json::Value) -> serde_json::Value {
+fn convert_claude_to_opencode_with_report(
+    config: &serde_json::Value,
+) -> (serde_json::Value, ConversionReport) {
     let mut result = serde_json::Map::new();
-    
-    // Infer type from config content
-    let mcp_type = if config.get("url").is_some() {
-        "remote"
-    } else {
-        "local"
+    let mut report = ConversionReport {
+        dropped: dropped_fields(config, adapter::CLAUDE.mapped_fields()),
+        ..Default::default()
     };
+    
+    // Infer type from the transport; OpenCode has no way to tell SSE from
+    // streamable HTTP, the sidecar remembers which one it was
+    let mcp_type = McpTransport::of_claude(config).map_or("local", McpTransport::opencode_type);
     result.insert("type".to_string(), serde_json::json!(mcp_type));
+    if config.get("type").is_none() {
+        report.inferred.push("type".to_string());
+    }
     
     // Always set enabled to true when syncing to OpenCode
     result.insert("enabled".to_string(), serde_json::json!(true));
+    report.inferred.push("enabled".to_string());
     
     // Merge command + args into single array
     let mut merged_command = Vec::new();
@@ -247,6 +817,7 @@ fn convert_claude_to_opencode(config: &serde_json::Value) -> serde_json::Value {
     
     if let Some(args) = config.get("args").and_then(|a| a.as_array()) {
         merged_command.extend(args.clone());
+        report.rename("args", "command[1..]");
     }
     
     if !merged_command.is_empty() {
@@ -256,6 +827,7 @@ fn convert_claude_to_opencode(config: &serde_json::Value) -> serde_json::Value {
     // Convert env -> environment
     if let Some(env) = config.get("env") {
         result.insert("environment".to_string(), env.clone());
+        report.rename("env", "environment");
     }
     
     // Copy common fields that both formats support
@@ -264,304 +836,931 @@ fn convert_claude_to_opencode(config: &serde_json::Value) -> serde_json::Value {
             result.insert(key.to_string(), value.clone());
         }
     }
-    
-    serde_json::Value::Object(result)
+
+    // ${VAR} and ${VAR:-default} -> {env:VAR}
+    translate_refs(
+        &mut result,
+        &OPENCODE_REF_FIELDS,
+        refs::Syntax::Claude,
+        refs::Syntax::Opencode,
+        &mut report.warnings,
+    );
+
+    (serde_json::Value::Object(result), report)
 }
 
 // ============================================================================
 // Path Functions
 // ============================================================================
 
+/// Resolver for the current environment and saved path overrides.
+fn path_resolver() -> PathResolver {
+    PathResolver::new(Environment::current(), load_app_settings().path_overrides)
+}
+
 fn get_opencode_config_path() -> PathBuf {
-    if cfg!(target_os = "windows") {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("opencode")
-            .join("opencode.json")
-    } else {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".config")
-            .join("opencode")
-            .join("opencode.json")
-    }
+    path_resolver().opencode_config().path
 }
 
 fn get_oh_my_opencode_path() -> PathBuf {
-    if cfg!(target_os = "windows") {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("opencode")
-            .join("oh-my-opencode.json")
-    } else {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".config")
-            .join("opencode")
-            .join("oh-my-opencode.json")
-    }
+    path_resolver().skills_config().path
 }
 
 fn get_claude_config_path() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".claude.json")
+    path_resolver().claude_config().path
 }
 
-
-
-fn get_backup_directory() -> PathBuf {
-    if cfg!(target_os = "windows") {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("OpenClaude-Tools")
-            .join("backups")
-    } else {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".config")
-            .join("openclaude-tools")
-            .join("backups")
-    }
+fn get_claude_desktop_config_path() -> PathBuf {
+    path_resolver().claude_desktop_config().path
 }
 
-// ============================================================================
-// Internal Config Loaders
-// ============================================================================
-
-fn load_opencode_config() -> Result<OpenCodeConfig, String> {
-    let path = get_opencode_config_path();
-    log::info!("Loading OpenCode config from: {:?}", path);
-
-    if !path.exists() {
-        log::warn!("OpenCode config not found: {:?}", path);
-        return Ok(OpenCodeConfig::default());
-    }
-
-    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read config: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))
+fn get_cursor_config_path() -> PathBuf {
+    path_resolver().cursor_config().path
 }
 
-fn save_opencode_config_internal(config: &OpenCodeConfig) -> Result<(), String> {
-    let path = get_opencode_config_path();
-    log::info!("Saving OpenCode config to: {:?}", path);
-
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
-    }
-
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-
-    fs::write(&path, content).map_err(|e| format!("Failed to write config: {}", e))
+/// Where Claude Code installs plugins (`~/.claude/plugins`).
+fn get_claude_plugins_directory() -> PathBuf {
+    path_resolver().claude_config_dir().path.join("plugins")
 }
 
-fn load_claude_config() -> Result<ClaudeConfig, String> {
-    let path = get_claude_config_path();
-    log::info!("Loading Claude config from: {:?}", path);
-
-    if !path.exists() {
-        log::warn!("Claude config not found: {:?}", path);
-        return Ok(ClaudeConfig::default());
-    }
-
-    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read config: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))
+/// Directory holding the app's own settings, backups and snapshots.
+fn get_app_config_directory() -> PathBuf {
+    Environment::current().app_config_dir()
 }
 
-fn save_claude_config_internal(config: &ClaudeConfig) -> Result<(), String> {
-    let path = get_claude_config_path();
-    log::info!("Saving Claude config to: {:?}", path);
+fn get_backup_directory() -> PathBuf {
+    path_resolver().backup_dir().path
+}
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
-    }
+fn get_app_settings_path() -> PathBuf {
+    get_app_config_directory().join("settings.json")
+}
 
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+/// Merge base of bidirectional sync: both sides of each server as last synced.
+fn get_sync_base_path() -> PathBuf {
+    get_app_config_directory().join("sync-base.json")
+}
 
-    fs::write(&path, content).map_err(|e| format!("Failed to write config: {}", e))
+/// Both sides of every converted server, for lossless round trips.
+fn get_conversion_meta_path() -> PathBuf {
+    get_app_config_directory().join("conversion-meta.json")
 }
 
+/// Journal of every MCP change the app made.
+fn get_history_path() -> PathBuf {
+    get_app_config_directory().join("history.jsonl")
+}
 
+/// Where disabled user and local scope Claude servers are kept.
+fn get_disabled_mcp_path() -> PathBuf {
+    get_app_config_directory().join("disabled-mcp.json")
+}
 
 // ============================================================================
-// MCP Commands - Simplified API
+// Internal Config Loaders
 // ============================================================================
 
-#[tauri::command]
-fn get_mcp_list() -> Result<MCPList, String> {
-    log::info!("Getting MCP list");
-    let mut list = MCPList::default();
+fn load_app_settings() -> AppSettings {
+    app_settings::load(&get_app_settings_path()).unwrap_or_else(|e| {
+        log::warn!("Failed to load app settings, using defaults: {}", e);
+        AppSettings::default()
+    })
+}
 
-    // Load OpenCode MCPs
-    let opencode_config = load_opencode_config()?;
-    for (name, config_value) in opencode_config.mcp {
-        let config_str = serde_json::to_string_pretty(&config_value)
-            .unwrap_or_else(|_| config_value.to_string());
-
-        // Extract enabled status from config
-        let enabled = config_value
-            .get("enabled")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true);
-
-        let description = config_value
-            .get("description")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-
-        list.opencode.push(MCPItem {
-            name,
-            config: config_str,
-            source: "opencode".to_string(),
-            enabled,
-            description,
-        });
-    }
+/// `ConfigFile::source` labels, which are also the snapshot name prefixes.
+/// No label may be another label followed by `_`, or pruning would mix them.
+const SNAPSHOT_SOURCES: [&str; 7] = [
+    "opencode",
+    "claude",
+    "claude-desktop",
+    "cursor",
+    "skills",
+    "project",
+    "settings",
+];
+
+/// How many times a load → mutate → save cycle is retried when another
+/// process rewrites the file underneath us.
+const MAX_WRITE_ATTEMPTS: usize = 3;
+
+/// A config file managed by the app: where it lives, how to read and write
+/// it, and where snapshots are taken before it is modified.
+struct ConfigFile<T> {
+    source: &'static str,
+    path: PathBuf,
+    /// Directory receiving automatic snapshots; `None` disables them.
+    snapshot_dir: Option<PathBuf>,
+    parse: fn(&str) -> serde_json::Result<T>,
+    render: fn(&T, Option<&str>) -> serde_json::Result<String>,
+}
 
-    // Load Claude MCPs from .claude.json
-    let claude_config = load_claude_config();
-    if let Ok(config) = claude_config {
-        // From mcpServers
-        for (name, config_value) in config.mcp_servers {
-            let config_str = serde_json::to_string_pretty(&config_value)
-                .unwrap_or_else(|_| config_value.to_string());
-
-            list.claude.push(MCPItem {
-                name,
-                config: config_str,
-                source: "claude".to_string(),
-                enabled: true,
-                description: None,
-            });
+impl<T: Default> ConfigFile<T> {
+    fn load(&self) -> Result<T, ConfigError> {
+        log::info!("Loading {} config from: {:?}", self.source, self.path);
+
+        if !self.path.exists() {
+            log::warn!("{} config not found: {:?}", self.source, self.path);
+            return Ok(T::default());
         }
+
+        let content =
+            fs::read_to_string(&self.path).map_err(|e| ConfigError::read(&self.path, e))?;
+        self.parse_content(&content)
     }
 
+    fn parse_content(&self, content: &str) -> Result<T, ConfigError> {
+        (self.parse)(content).map_err(|e| ConfigError::parse(Some(&self.path), &e))
+    }
 
-    log::info!(
-        "Found {} OpenCode MCPs, {} Claude MCPs",
-        list.opencode.len(),
-        list.claude.len()
-    );
+    fn render_content(&self, config: &T, existing: Option<&str>) -> Result<String, ConfigError> {
+        (self.render)(config, existing).map_err(ConfigError::internal)
+    }
 
-    Ok(list)
-}
+    /// Overwrite the file with `config`, snapshotting the previous content.
+    /// Goes through `update`, so a concurrent write is never clobbered
+    /// between reading the file for its layout and replacing it.
+    fn save(&self, config: &T) -> Result<(), ConfigError>
+    where
+        T: Clone,
+    {
+        log::info!("Saving {} config to: {:?}", self.source, self.path);
+        self.update(|current| {
+            *current = config.clone();
+            Ok(())
+        })
+    }
 
-#[tauri::command]
-fn add_mcp(
-    name: String,
-    config_json: String,
-    source: String,
-    description: Option<String>,
-) -> Result<(), String> {
-    log::info!("Adding MCP: {} to {}", name, source);
+    /// Run `mutate` against the file and save the result, guarding against
+    /// concurrent writers.
+    ///
+    /// The file's fingerprint is captured at load time and checked right
+    /// before the save. If it changed (Claude Code rewrites `~/.claude.json`
+    /// constantly), the file is reloaded and `mutate` is applied again to the
+    /// fresh content, so the other writer's changes are kept. After
+    /// `MAX_WRITE_ATTEMPTS` a `ConfigError::Conflict` is returned instead of
+    /// clobbering the file.
+    fn update<R>(
+        &self,
+        mut mutate: impl FnMut(&mut T) -> Result<R, ConfigError>,
+    ) -> Result<R, ConfigError> {
+        log::info!("Updating {} config at: {:?}", self.source, self.path);
+
+        for attempt in 1..=MAX_WRITE_ATTEMPTS {
+            let (existing, fingerprint) = file_io::read_with_fingerprint(&self.path)
+                .map_err(|e| ConfigError::read(&self.path, e))?;
+            let mut config = match &existing {
+                Some(content) => self.parse_content(content)?,
+                None => T::default(),
+            };
+
+            let result = mutate(&mut config)?;
+            let content = self.render_content(&config, existing.as_deref())?;
+            if existing.as_deref() == Some(content.as_str()) {
+                return Ok(result);
+            }
+            let snapshot = match &existing {
+                Some(previous) => self.snapshot(previous)?,
+                None => None,
+            };
+
+            let outcome = file_io::write_atomic_if_unchanged(&self.path, content.as_bytes(), &fingerprint);
+            if !matches!(outcome, Ok(WriteOutcome::Written)) {
+                // Nothing was overwritten, so the snapshot must not use up retention
+                if let Some(snapshot) = &snapshot {
+                    if let Err(e) = fs::remove_file(snapshot) {
+                        log::warn!("Failed to remove unused snapshot {:?}: {}", snapshot, e);
+                    }
+                }
+            }
+            match outcome.map_err(|e| ConfigError::write(&self.path, e))? {
+                WriteOutcome::Written => {
+                    self.prune_snapshots();
+                    return Ok(result);
+                }
+                WriteOutcome::Conflict => log::warn!(
+                    "{:?} changed on disk while saving (attempt {}/{}), retrying",
+                    self.path,
+                    attempt,
+                    MAX_WRITE_ATTEMPTS
+                ),
+            }
+        }
 
-    // Parse the config JSON to validate it
-    let config_value: serde_json::Value =
-        serde_json::from_str(&config_json).map_err(|e| format!("Invalid JSON config: {}", e))?;
+        Err(ConfigError::Conflict {
+            path: self.path.to_string_lossy().to_string(),
+        })
+    }
 
-    if source == "opencode" {
-        let mut opencode_config = load_opencode_config()?;
+    /// Keep a copy of `content` before it gets overwritten. A failed snapshot
+    /// aborts the write: modifying a file we could not back up is not allowed.
+    fn snapshot(&self, content: &str) -> Result<Option<PathBuf>, ConfigError> {
+        let Some(dir) = &self.snapshot_dir else {
+            return Ok(None);
+        };
+        snapshot::write_snapshot(dir, self.source, &self.path, content)
+            .map(Some)
+            .map_err(|e| ConfigError::write(dir, e))
+    }
 
-        // Add enabled and description if provided
-        let mut final_config = config_value.clone();
-        if let Some(obj) = final_config.as_object_mut() {
-            if !obj.contains_key("enabled") {
-                obj.insert("enabled".to_string(), serde_json::json!(true));
-            }
-            if let Some(desc) = description {
-                obj.insert("description".to_string(), serde_json::json!(desc));
+    /// Apply the retention policy once a snapshotted write went through.
+    fn prune_snapshots(&self) {
+        if let Some(dir) = &self.snapshot_dir {
+            let retention = load_app_settings().snapshot_retention;
+            if let Err(e) = snapshot::prune(dir, self.source, &retention, std::time::SystemTime::now()) {
+                log::warn!("Failed to prune snapshots for {}: {}", self.source, e);
             }
         }
+    }
+}
 
-        opencode_config.mcp.insert(name, final_config);
-        save_opencode_config_internal(&opencode_config)?;
-    } else {
-        let mut claude_config = load_claude_config()?;
+fn opencode_config_file() -> ConfigFile<OpenCodeConfig> {
+    ConfigFile {
+        source: "opencode",
+        path: get_opencode_config_path(),
+        snapshot_dir: Some(get_backup_directory()),
+        parse: parse_opencode_config,
+        render: render_opencode_config,
+    }
+}
 
-        // Add to mcpServers in .claude.json
-        claude_config.mcp_servers.insert(name, config_value);
-        save_claude_config_internal(&claude_config)?;
+fn claude_config_file() -> ConfigFile<ClaudeConfig> {
+    ConfigFile {
+        source: "claude",
+        path: get_claude_config_path(),
+        snapshot_dir: Some(get_backup_directory()),
+        parse: parse_claude_config,
+        render: render_claude_config,
     }
+}
 
-    Ok(())
+fn claude_desktop_config_file() -> ConfigFile<ClaudeDesktopConfig> {
+    ConfigFile {
+        source: "claude-desktop",
+        path: get_claude_desktop_config_path(),
+        snapshot_dir: Some(get_backup_directory()),
+        parse: parse_claude_desktop_config,
+        render: render_claude_desktop_config,
+    }
 }
 
-#[tauri::command]
-fn update_mcp(
-    name: String,
-    config_json: String,
-    source: String,
-    description: Option<String>,
-) -> Result<(), String> {
-    log::info!("Updating MCP: {} in {}", name, source);
+fn skills_config_file() -> ConfigFile<OhMyOpenCodeConfig> {
+    ConfigFile {
+        source: "skills",
+        path: get_oh_my_opencode_path(),
+        snapshot_dir: Some(get_backup_directory()),
+        parse: parse_skills_config,
+        render: render_skills_config,
+    }
+}
 
-    // Parse the config JSON to validate it
-    let config_value: serde_json::Value =
-        serde_json::from_str(&config_json).map_err(|e| format!("Invalid JSON config: {}", e))?;
+/// Check that `dir` is usable as a project directory.
+fn check_project_dir(dir: &Path) -> Result<(), ConfigError> {
+    if !dir.is_absolute() {
+        return Err(ConfigError::InvalidInput(format!(
+            "Project path must be absolute: {}",
+            dir.display()
+        )));
+    }
+    if !dir.is_dir() {
+        return Err(ConfigError::FileNotFound {
+            path: dir.to_string_lossy().to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// The `.mcp.json` of project directory `dir`.
+fn project_mcp_file(dir: &Path) -> Result<ConfigFile<ProjectMcpConfig>, ConfigError> {
+    check_project_dir(dir)?;
+    Ok(ConfigFile {
+        source: "project",
+        path: dir.join(".mcp.json"),
+        snapshot_dir: Some(get_backup_directory()),
+        parse: parse_project_mcp_config,
+        render: render_project_mcp_config,
+    })
+}
+
+fn cursor_config_file() -> ConfigFile<ProjectMcpConfig> {
+    ConfigFile {
+        source: "cursor",
+        path: get_cursor_config_path(),
+        snapshot_dir: Some(get_backup_directory()),
+        parse: parse_project_mcp_config,
+        render: render_project_mcp_config,
+    }
+}
 
-    if source == "opencode" {
-        let mut opencode_config = load_opencode_config()?;
+/// The `.cursor/mcp.json` of project directory `dir`.
+fn cursor_project_file(dir: &Path) -> Result<ConfigFile<ProjectMcpConfig>, ConfigError> {
+    check_project_dir(dir)?;
+    Ok(ConfigFile {
+        source: "cursor",
+        path: dir.join(".cursor").join("mcp.json"),
+        snapshot_dir: Some(get_backup_directory()),
+        parse: parse_project_mcp_config,
+        render: render_project_mcp_config,
+    })
+}
 
-        if !opencode_config.mcp.contains_key(&name) {
-            return Err(format!("MCP '{}' not found in OpenCode config", name));
+/// Path of the Claude Code settings file of `scope`; `project` is required
+/// for the project and local scopes.
+fn claude_settings_path(scope: SettingsScope, project: Option<&str>) -> Result<PathBuf, ConfigError> {
+    if scope == SettingsScope::User {
+        return Ok(path_resolver().claude_config_dir().path.join("settings.json"));
+    }
+    let dir = Path::new(project.ok_or_else(|| {
+        ConfigError::InvalidInput("A project path is required for project settings".to_string())
+    })?);
+    check_project_dir(dir)?;
+    let name = if scope == SettingsScope::Local {
+        "settings.local.json"
+    } else {
+        "settings.json"
+    };
+    Ok(dir.join(".claude").join(name))
+}
+
+fn claude_settings_file(
+    scope: SettingsScope,
+    project: Option<&str>,
+) -> Result<ConfigFile<ClaudeSettings>, ConfigError> {
+    Ok(ConfigFile {
+        source: "settings",
+        path: claude_settings_path(scope, project)?,
+        snapshot_dir: Some(get_backup_directory()),
+        parse: parse_claude_settings,
+        render: render_claude_settings,
+    })
+}
+
+fn parse_opencode_config(content: &str) -> serde_json::Result<OpenCodeConfig> {
+    jsonc::parse(content)
+}
+
+fn load_opencode_config() -> Result<OpenCodeConfig, ConfigError> {
+    opencode_config_file().load()
+}
+
+fn save_opencode_config_internal(config: &OpenCodeConfig) -> Result<(), ConfigError> {
+    opencode_config_file().save(config)
+}
+
+/// Load, mutate and save the OpenCode config without clobbering concurrent edits.
+fn update_opencode_config<R>(
+    mutate: impl FnMut(&mut OpenCodeConfig) -> Result<R, ConfigError>,
+) -> Result<R, ConfigError> {
+    opencode_config_file().update(mutate)
+}
+
+/// Serialize the OpenCode config, editing `existing` in place when possible so
+/// comments and layout in the user's file survive.
+fn render_opencode_config(
+    config: &OpenCodeConfig,
+    existing: Option<&str>,
+) -> serde_json::Result<String> {
+    let value = serde_json::to_value(config)?;
+
+    if let Some(text) = existing {
+        match jsonc::update(text, &value) {
+            Ok(updated) => return Ok(updated),
+            Err(e) => log::warn!("Could not edit OpenCode config in place, rewriting it: {}", e),
         }
+    }
+
+    serde_json::to_string_pretty(&value)
+}
+
+fn parse_claude_config(content: &str) -> serde_json::Result<ClaudeConfig> {
+    serde_json::from_str(content)
+}
 
-        // Add description if provided
-        let mut final_config = config_value.clone();
-        if let (Some(obj), Some(desc)) = (final_config.as_object_mut(), description) {
-            obj.insert("description".to_string(), serde_json::json!(desc));
+/// Edit `~/.claude.json` in place so its key order and layout survive. Empty
+/// `mcpServers` maps the file did not have before are left out.
+fn render_claude_config(config: &ClaudeConfig, existing: Option<&str>) -> serde_json::Result<String> {
+    let mut value = serde_json::to_value(config)?;
+
+    if let Some(text) = existing {
+        let old: serde_json::Value = jsonc::parse(text).unwrap_or_default();
+        drop_added_empty_servers(&mut value, &old);
+        if let Some(projects) = value.get_mut("projects").and_then(serde_json::Value::as_object_mut) {
+            for (path, project) in projects {
+                drop_added_empty_servers(project, &old["projects"][path.as_str()]);
+            }
+        }
+        match jsonc::update(text, &value) {
+            Ok(updated) => return Ok(updated),
+            Err(e) => log::warn!("Could not edit Claude config in place, rewriting it: {}", e),
         }
+    }
 
-        opencode_config.mcp.insert(name, final_config);
-        save_opencode_config_internal(&opencode_config)?;
-    } else {
-        let mut claude_config = load_claude_config()?;
+    serde_json::to_string_pretty(&value)
+}
+
+fn drop_added_empty_servers(value: &mut serde_json::Value, old: &serde_json::Value) {
+    let empty = value["mcpServers"].as_object().is_some_and(|servers| servers.is_empty());
+    if empty && old.get("mcpServers").is_none() {
+        if let Some(object) = value.as_object_mut() {
+            object.remove("mcpServers");
+        }
+    }
+}
+
+fn load_claude_config() -> Result<ClaudeConfig, ConfigError> {
+    claude_config_file().load()
+}
+
+fn save_claude_config_internal(config: &ClaudeConfig) -> Result<(), ConfigError> {
+    claude_config_file().save(config)
+}
+
+fn parse_skills_config(content: &str) -> serde_json::Result<OhMyOpenCodeConfig> {
+    serde_json::from_str(content)
+}
+
+fn render_skills_config(
+    config: &OhMyOpenCodeConfig,
+    _existing: Option<&str>,
+) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(config)
+}
+
+fn parse_claude_settings(content: &str) -> serde_json::Result<ClaudeSettings> {
+    serde_json::from_str(content)
+}
 
-        if !claude_config.mcp_servers.contains_key(&name) {
-            return Err(format!("MCP '{}' not found in Claude config", name));
+fn render_claude_settings(
+    settings: &ClaudeSettings,
+    _existing: Option<&str>,
+) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(settings)
+}
+
+fn parse_project_mcp_config(content: &str) -> serde_json::Result<ProjectMcpConfig> {
+    serde_json::from_str(content)
+}
+
+fn render_project_mcp_config(
+    config: &ProjectMcpConfig,
+    _existing: Option<&str>,
+) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(config)
+}
+
+fn parse_claude_desktop_config(content: &str) -> serde_json::Result<ClaudeDesktopConfig> {
+    serde_json::from_str(content)
+}
+
+fn render_claude_desktop_config(
+    config: &ClaudeDesktopConfig,
+    _existing: Option<&str>,
+) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(config)
+}
+
+/// Load, mutate and save the Claude servers of `scope`, wherever they are
+/// stored: ~/.claude.json for user and local scope, `.mcp.json` for project
+/// scope. With `create`, a missing local-scope project entry is added;
+/// otherwise `mutate` receives `None` for it.
+fn update_claude_servers<R>(
+    scope: &McpScope,
+    create: bool,
+    mut mutate: impl FnMut(Option<&mut McpServers>) -> Result<R, ConfigError>,
+) -> Result<R, ConfigError> {
+    match scope {
+        McpScope::Plugin { plugin, .. } => Err(plugin_read_only(plugin)),
+        McpScope::Project { path } => project_mcp_file(Path::new(path))?
+            .update(|config| mutate(Some(&mut config.mcp_servers))),
+        _ => update_scope_servers(&claude_config_file(), scope, create, mutate),
+    }
+}
+
+/// `update_claude_servers` for the user and local scopes of `file`.
+fn update_scope_servers<R>(
+    file: &ConfigFile<ClaudeConfig>,
+    scope: &McpScope,
+    create: bool,
+    mut mutate: impl FnMut(Option<&mut McpServers>) -> Result<R, ConfigError>,
+) -> Result<R, ConfigError> {
+    file.update(|config| {
+        if create {
+            mutate(config.servers_entry(scope))
+        } else {
+            mutate(config.servers_mut(scope))
         }
+    })
+}
+
+fn plugin_read_only(plugin: &str) -> ConfigError {
+    ConfigError::InvalidInput(format!(
+        "MCP servers of plugin {} are read-only; sync them to another scope instead",
+        plugin
+    ))
+}
+
+/// Load, mutate and save the skills config without clobbering concurrent edits.
+fn update_skills_config(
+    mutate: impl FnMut(&mut OhMyOpenCodeConfig) -> Result<(), ConfigError>,
+) -> Result<(), ConfigError> {
+    skills_config_file().update(mutate)
+}
+
+/// Parse a JSON document handed over by the frontend.
+fn parse_json_input(json: &str) -> Result<serde_json::Value, ConfigError> {
+    serde_json::from_str(json).map_err(|e| ConfigError::parse(None, &e))
+}
+
+/// Reject an MCP entry that `tool` would fail to load, unless `force` is set.
+fn validate_mcp(
+    tool: &dyn ToolAdapter,
+    name: &str,
+    config: &serde_json::Value,
+    force: Option<bool>,
+) -> Result<(), ConfigError> {
+    let errors = tool.validate(name, config);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    if force.unwrap_or(false) {
+        log::warn!("Writing invalid MCP '{}' (forced): {}", name, errors.join("; "));
+        return Ok(());
+    }
+    Err(ConfigError::ValidationError { errors })
+}
+
+/// Journal a change. It has already been written, so a journal failure is
+/// only logged.
+fn record_history(entry: HistoryEntry) {
+    if let Err(e) = history::append(&get_history_path(), &entry) {
+        log::warn!("Could not record history for MCP '{}': {}", entry.name, e);
+    }
+}
+
+/// Serialize data the app writes itself (backups, exports).
+fn to_json_pretty<T: Serialize + ?Sized>(value: &T) -> Result<String, ConfigError> {
+    serde_json::to_string_pretty(value).map_err(ConfigError::internal)
+}
+
+// ============================================================================
+// MCP Commands - Simplified API
+// ============================================================================
+
+#[tauri::command]
+fn get_mcp_list() -> Result<MCPList, ConfigError> {
+    log::info!("Getting MCP list");
+    let mut list = MCPList::default();
 
-        claude_config.mcp_servers.insert(name, config_value);
-        save_claude_config_internal(&claude_config)?;
+    for tool in adapter::ADAPTERS {
+        let items = match tool.list() {
+            Ok(items) => items,
+            // A broken Claude Desktop or Cursor file does not hide the rest
+            Err(e) if !matches!(tool.id(), "opencode" | "claude") => {
+                log::warn!("Skipping {} MCPs: {}", tool.label(), e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        for item in items {
+            match (&item.scope, item.source.as_str()) {
+                (McpScope::Project { .. }, "claude") => list.project.push(item),
+                (McpScope::Plugin { .. }, "claude") => list.plugin.push(item),
+                (_, "opencode") => list.opencode.push(item),
+                (_, "claude") => list.claude.push(item),
+                (_, other) => list.other.entry(other.to_string()).or_default().push(item),
+            }
+        }
     }
 
+    log::info!(
+        "Found {} OpenCode MCPs, {} Claude MCPs, {} project MCPs, {} plugin MCPs",
+        list.opencode.len(),
+        list.claude.len(),
+        list.project.len(),
+        list.plugin.len()
+    );
+
+    Ok(list)
+}
+
+#[tauri::command]
+fn add_mcp(
+    name: String,
+    config_json: String,
+    source: String,
+    description: Option<String>,
+    scope: Option<McpScope>,
+    force: Option<bool>,
+) -> Result<(), ConfigError> {
+    log::info!("Adding MCP: {} to {}", name, source);
+    let tool = adapter::adapter(&source)?;
+    let scope = scope.unwrap_or_default();
+
+    // Parse the config JSON to validate it
+    let mut config_value = parse_json_input(&config_json)?;
+    tool.prepare_entry(&mut config_value, description, true);
+    validate_mcp(tool, &name, &config_value, force)?;
+
+    // Add to the servers of the scope
+    let before = tool.update_servers(&scope, true, &mut |servers| {
+        Ok(servers.and_then(|servers| servers.insert(name.clone(), config_value.clone())))
+    })?;
+    record_history(HistoryEntry::new(
+        HistoryAction::Add,
+        &source,
+        &source,
+        &scope,
+        &name,
+        before,
+        Some(config_value),
+    ));
+
     Ok(())
 }
 
 #[tauri::command]
-fn delete_mcp(name: String, source: String) -> Result<(), String> {
+fn update_mcp(
+    name: String,
+    config_json: String,
+    source: String,
+    description: Option<String>,
+    scope: Option<McpScope>,
+    force: Option<bool>,
+) -> Result<(), ConfigError> {
+    log::info!("Updating MCP: {} in {}", name, source);
+    let tool = adapter::adapter(&source)?;
+    let scope = scope.unwrap_or_default();
+
+    // Parse the config JSON to validate it
+    let mut config_value = parse_json_input(&config_json)?;
+    tool.prepare_entry(&mut config_value, description, false);
+    validate_mcp(tool, &name, &config_value, force)?;
+
+    record_history(edit_server(tool, HistoryAction::Update, &name, &scope, Some(config_value))?);
+
+    Ok(())
+}
+
+#[tauri::command]
+fn delete_mcp(name: String, source: String, scope: Option<McpScope>) -> Result<(), ConfigError> {
     log::info!("Deleting MCP: {} from {}", name, source);
+    let tool = adapter::adapter(&source)?;
+    let scope = scope.unwrap_or_default();
 
-    if source == "opencode" {
-        let mut opencode_config = load_opencode_config()?;
+    record_history(edit_server(tool, HistoryAction::Delete, &name, &scope, None)?);
+    forget_conversion(&name, &source, &scope);
 
-        if opencode_config.mcp.remove(&name).is_none() {
-            return Err(format!("MCP '{}' not found in OpenCode config", name));
+    Ok(())
+}
+
+/// Replace (`Some`) or delete (`None`) existing server `name` of `scope`. A
+/// disabled server is changed where it is stored. Returns the history entry.
+fn edit_server(
+    tool: &dyn ToolAdapter,
+    action: HistoryAction,
+    name: &str,
+    scope: &McpScope,
+    config: Option<serde_json::Value>,
+) -> Result<HistoryEntry, ConfigError> {
+    let mut edit = |servers: Option<&mut McpServers>| match servers {
+        Some(servers) if servers.contains_key(name) => Ok(match &config {
+            Some(config) => servers.insert(name.to_string(), config.clone()),
+            None => servers.remove(name),
+        }),
+        _ => Err(ConfigError::mcp_not_found(name, &tool.scope_label(scope))),
+    };
+    let (before, disabled) = match tool.update_servers(scope, false, &mut edit) {
+        Err(ConfigError::McpNotFound { .. }) => (tool.update_disabled(scope, &mut edit)?, true),
+        result => (result?, false),
+    };
+
+    let mut entry = HistoryEntry::new(action, tool.id(), tool.id(), scope, name, before, config.clone());
+    entry.disabled = disabled;
+    Ok(entry)
+}
+
+/// Drop the conversion metadata of server `name` in `source`'s `scope` after
+/// it was removed there, so a new server reusing the name starts clean.
+/// Failures are only logged: the metadata is a cache.
+fn forget_conversion(name: &str, source: &str, scope: &McpScope) {
+    let meta_path = get_conversion_meta_path();
+    let result = sidecar::load(&meta_path).and_then(|mut meta| {
+        if meta.remove(name, (source, scope)) {
+            sidecar::save(&meta_path, &meta)?;
         }
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to forget conversion metadata of MCP '{}': {}", name, e);
+    }
+}
 
-        save_opencode_config_internal(&opencode_config)?;
-    } else {
-        let mut claude_config = load_claude_config()?;
+/// Enable or disable an MCP server without losing its config; see each
+/// tool's `ToolAdapter::set_enabled` for how.
+#[tauri::command]
+fn toggle_mcp(
+    name: String,
+    source: String,
+    enabled: bool,
+    scope: Option<McpScope>,
+) -> Result<(), ConfigError> {
+    log::info!(
+        "{} MCP: {} in {}",
+        if enabled { "Enabling" } else { "Disabling" },
+        name,
+        source
+    );
+    let scope = scope.unwrap_or_default();
+    adapter::adapter(&source)?.set_enabled(&name, &scope, enabled)
+}
 
-        if claude_config.mcp_servers.remove(&name).is_none() {
-            return Err(format!("MCP '{}' not found in Claude config", name));
+/// Move a user or local scope server between `~/.claude.json` and the
+/// disabled store. Toggling to the current state is a no-op.
+fn toggle_claude_server(name: &str, scope: &McpScope, enabled: bool) -> Result<(), ConfigError> {
+    if let McpScope::Plugin { plugin, .. } = scope {
+        return Err(plugin_read_only(plugin));
+    }
+    toggle_claude_server_in(&claude_config_file(), &get_disabled_mcp_path(), name, scope, enabled)
+}
+
+/// `toggle_claude_server` against `file` and the disabled store at `store_path`.
+fn toggle_claude_server_in(
+    file: &ConfigFile<ClaudeConfig>,
+    store_path: &Path,
+    name: &str,
+    scope: &McpScope,
+    enabled: bool,
+) -> Result<(), ConfigError> {
+    let mut store = disabled_mcp::load(store_path)?;
+    let stored = store.get(name, scope).map(|s| s.config.clone());
+
+    if enabled {
+        let Some(config) = stored else {
+            // Not stored: fine if it is already live
+            return update_scope_servers(file, scope, false, |servers| match servers {
+                Some(servers) if servers.contains_key(name) => Ok(()),
+                _ => Err(ConfigError::mcp_not_found(name, &scope.claude_label())),
+            });
+        };
+        update_scope_servers(file, scope, true, |servers| {
+            let Some(servers) = servers else {
+                return Ok(());
+            };
+            if servers.contains_key(name) {
+                return Err(ConfigError::InvalidInput(format!(
+                    "MCP '{}' already exists in {} config; rename or delete it before enabling the disabled one",
+                    name,
+                    scope.claude_label()
+                )));
+            }
+            servers.insert(name.to_string(), config.clone());
+            Ok(())
+        })?;
+        store.remove(name, scope);
+        return disabled_mcp::save(store_path, &store);
+    }
+
+    let mut removed = None;
+    update_scope_servers(file, scope, false, |servers| {
+        removed = servers.and_then(|servers| servers.remove(name));
+        if removed.is_none() && stored.is_none() {
+            return Err(ConfigError::mcp_not_found(name, &scope.claude_label()));
         }
+        Ok(())
+    })?;
+    let Some(config) = removed else {
+        return Ok(());
+    };
+
+    store.insert(name, scope, config.clone());
+    if let Err(e) = disabled_mcp::save(store_path, &store) {
+        // Put the server back rather than lose it
+        update_scope_servers(file, scope, true, |servers| {
+            if let Some(servers) = servers {
+                servers.insert(name.to_string(), config.clone());
+            }
+            Ok(())
+        })?;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Disable a project `.mcp.json` server through the project's local settings,
+/// or clear it from both settings files' `disabledMcpjsonServers` and
+/// approve it again.
+fn toggle_project_server(name: &str, project: &str, enabled: bool) -> Result<(), ConfigError> {
+    toggle_project_server_in(
+        &project_mcp_file(Path::new(project))?,
+        &claude_settings_file(SettingsScope::Local, Some(project))?,
+        &claude_settings_file(SettingsScope::Project, Some(project))?,
+        name,
+        enabled,
+    )
+}
 
-        save_claude_config_internal(&claude_config)?;
+/// `toggle_project_server` against the project's `.mcp.json` and its local
+/// and shared settings files.
+fn toggle_project_server_in(
+    mcp: &ConfigFile<ProjectMcpConfig>,
+    local: &ConfigFile<ClaudeSettings>,
+    shared: &ConfigFile<ClaudeSettings>,
+    name: &str,
+    enabled: bool,
+) -> Result<(), ConfigError> {
+    if !mcp.load()?.mcp_servers.contains_key(name) {
+        return Err(ConfigError::mcp_not_found(name, &mcp.path.to_string_lossy()));
     }
 
+    local.update(|settings| {
+        set_listed(&mut settings.disabled_mcpjson_servers, name, !enabled);
+        if settings.enable_all_project_mcp_servers != Some(true) {
+            set_listed(&mut settings.enabled_mcpjson_servers, name, enabled);
+        }
+        Ok(())
+    })?;
+
+    if enabled && shared.path.exists() {
+        shared.update(|settings| {
+            set_listed(&mut settings.disabled_mcpjson_servers, name, false);
+            Ok(())
+        })?;
+    }
     Ok(())
 }
 
+/// Add `name` to or remove it from a settings list such as
+/// `disabledMcpjsonServers`, creating the list only when adding.
+fn set_listed(list: &mut Option<Vec<String>>, name: &str, listed: bool) {
+    match list {
+        Some(names) => {
+            names.retain(|n| n != name);
+            if listed {
+                names.push(name.to_string());
+            }
+        }
+        None if listed => *list = Some(vec![name.to_string()]),
+        None => {}
+    }
+}
+
+/// Names listed in `disabledMcpjsonServers` of the project's shared or local settings.
+fn project_disabled_servers(project: &str) -> Vec<String> {
+    [SettingsScope::Project, SettingsScope::Local]
+        .into_iter()
+        .filter_map(|scope| claude_settings_file(scope, Some(project)).ok())
+        .filter(|file| file.path.exists())
+        .filter_map(|file| match file.load() {
+            Ok(settings) => settings.disabled_mcpjson_servers,
+            Err(e) => {
+                log::warn!("Could not read {:?}: {}", file.path, e);
+                None
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+/// Load, mutate and save the disabled Claude servers of `scope` in the store
+/// at `store_path`, presented like the live ones.
+fn update_disabled_servers(
+    store_path: &Path,
+    scope: &McpScope,
+    mutate: &mut adapter::Mutate,
+) -> Result<Option<serde_json::Value>, ConfigError> {
+    let mut store = disabled_mcp::load(store_path)?;
+    let before: McpServers = store
+        .servers
+        .iter()
+        .filter(|server| &server.scope == scope)
+        .map(|server| (server.name.clone(), server.config.clone()))
+        .collect();
+    let mut servers = before.clone();
+
+    let result = mutate(Some(&mut servers))?;
+    if servers != before {
+        store.servers.retain(|server| &server.scope != scope);
+        for (name, config) in servers {
+            store.insert(&name, scope, config);
+        }
+        disabled_mcp::save(store_path, &store)?;
+    }
+    Ok(result)
+}
+
+/// Copy MCP `name` to `to_source`, converting formats when needed. Returns
+/// what the conversion dropped, renamed or inferred; empty when none was needed.
 #[tauri::command]
 fn sync_mcp(
     name: String,
     from_source: String,
     to_source: String,
     config_json: String,
-) -> Result<(), String> {
+    from_scope: Option<McpScope>,
+    to_scope: Option<McpScope>,
+    force: Option<bool>,
+) -> Result<ConversionReport, ConfigError> {
+    let (from, to) = (adapter::adapter(&from_source)?, adapter::adapter(&to_source)?);
+    let (from_scope, to_scope) = (from_scope.unwrap_or_default(), to_scope.unwrap_or_default());
     log::info!(
         "Syncing MCP '{}' from {} to {}",
         name,
@@ -570,80 +1769,729 @@ fn sync_mcp(
     );
 
     // Parse the config JSON
-    let config_value: serde_json::Value =
-        serde_json::from_str(&config_json).map_err(|e| format!("Invalid JSON config: {}", e))?;
+    let config_value = parse_json_input(&config_json)?;
 
     // Convert config format based on target source
-    let converted_config = if from_source != to_source {
+    let meta_path = get_conversion_meta_path();
+    let mut meta = sidecar::load(&meta_path)?;
+    let (converted_config, report) = if from_source != to_source {
         // Need format conversion when syncing between different sources
-        if to_source == "opencode" {
-            log::info!("Converting Claude format to OpenCode format");
-            convert_claude_to_opencode(&config_value)
-        } else {
-            log::info!("Converting OpenCode format to Claude format");
-            convert_opencode_to_claude(&config_value)
-        }
+        log::info!("Converting {} format to {} format", from.label(), to.label());
+        adapter::convert(from, &from_scope, to, &to_scope, &mut meta, &name, &config_value)
     } else {
         // Same source, no conversion needed
-        config_value.clone()
+        (config_value.clone(), ConversionReport::default())
     };
 
     log::debug!("Converted config: {}", serde_json::to_string_pretty(&converted_config).unwrap_or_default());
+    validate_mcp(to, &name, &converted_config, force)?;
 
-    if to_source == "opencode" {
-        let mut opencode_config = load_opencode_config()?;
-        opencode_config.mcp.insert(name, converted_config);
-        save_opencode_config_internal(&opencode_config)?;
-    } else if to_source == "claude" {
-        let mut claude_config = load_claude_config()?;
-        claude_config.mcp_servers.insert(name, converted_config);
-        save_claude_config_internal(&claude_config)?;
+    let before = to.update_servers(&to_scope, true, &mut |servers| {
+        Ok(servers.and_then(|servers| servers.insert(name.clone(), converted_config.clone())))
+    })?;
+    if from_source != to_source {
+        sidecar::save(&meta_path, &meta)?;
+    }
+    record_history(HistoryEntry::new(
+        HistoryAction::Sync,
+        &from_source,
+        &to_source,
+        &to_scope,
+        &name,
+        before,
+        Some(converted_config),
+    ));
+    if !report.is_empty() {
+        log::info!("Conversion of MCP '{}': {:?}", name, report);
+    }
+    for warning in &report.warnings {
+        log::warn!("Synced MCP '{}': {}", name, warning);
     }
 
-    Ok(())
+    Ok(report)
 }
 
 // ============================================================================
-// Skills Commands
+// Sync Commands
 // ============================================================================
 
-#[tauri::command]
-fn get_skills_config() -> Result<OhMyOpenCodeConfig, String> {
-    let path = get_oh_my_opencode_path();
-    log::info!("Loading Skills config from: {:?}", path);
+/// What syncing `names` (every source server when `None`) from `source` into
+/// `target` between `tools` would change, comparing each converted config
+/// with the target's.
+fn preview_direction(
+    source: &McpServers,
+    target: &McpServers,
+    tools: ToolPair,
+    sidecar: &mut sidecar::Sidecar,
+    names: Option<&[String]>,
+) -> SyncDirectionPreview {
+    let selected = |name: &String| names.is_none_or(|names| names.contains(name));
+    let mut preview = SyncDirectionPreview::default();
+
+    for (name, config) in source.iter().filter(|(name, _)| selected(name)) {
+        match target.get(name) {
+            None => preview.added.push(name.clone()),
+            Some(existing) => {
+                let diff = diff::diff(existing, &tools.convert(sidecar, name, config));
+                if !diff.is_empty() {
+                    preview.updated.push(SyncUpdate {
+                        name: name.clone(),
+                        diff,
+                    });
+                }
+            }
+        }
+    }
+    preview.removed = target
+        .keys()
+        .filter(|name| selected(name) && !source.contains_key(*name))
+        .cloned()
+        .collect();
+
+    preview
+}
+
+/// When each side of a server last changed, as far as the app can tell, for
+/// `prefer-newer`. Config files are rewritten for all kinds of reasons, so
+/// their mtimes say nothing about a single server.
+struct Recency<'a> {
+    tools: ToolPair,
+    /// The pair's merge base: both sides of each server as last synced.
+    base: Option<&'a merge::PairBase>,
+    journal: &'a [HistoryEntry],
+}
 
-    if !path.exists() {
-        log::warn!("Skills config not found: {:?}", path);
-        return Ok(OhMyOpenCodeConfig::default());
+impl Recency<'_> {
+    /// `Some(true)` when the source version of `name` is the newer one,
+    /// `Some(false)` when the target's is, `None` when that cannot be told.
+    ///
+    /// If only one side changed since the last sync, that side is newer.
+    /// Otherwise each side's current version is dated by the history entry
+    /// that wrote it; versions the app did not write have no known date.
+    fn source_is_newer(&self, name: &str, source: &serde_json::Value, target: &serde_json::Value) -> Option<bool> {
+        if let Some(entry) = self.base.and_then(|base| base.get(name)) {
+            let (source_base, target_base) = match self.tools.ordered() {
+                (_, false) => (&entry.left, &entry.right),
+                (_, true) => (&entry.right, &entry.left),
+            };
+            match (source != source_base, target != target_base) {
+                (true, false) => return Some(true),
+                (false, true) => return Some(false),
+                _ => {}
+            }
+        }
+        let written_at = |tool: &dyn ToolAdapter, config: &serde_json::Value| {
+            let entry = self.journal.iter().rev().find(|entry| {
+                entry.target == tool.id() && entry.scope == McpScope::User && entry.name == name
+            })?;
+            if entry.after.as_ref() != Some(config) {
+                return None;
+            }
+            chrono::DateTime::parse_from_rfc3339(&entry.timestamp).ok()
+        };
+        match (written_at(self.tools.from, source), written_at(self.tools.to, target)) {
+            (Some(source), Some(target)) if source != target => Some(source > target),
+            _ => None,
+        }
     }
+}
 
-    let content =
-        fs::read_to_string(&path).map_err(|e| format!("Failed to read skills config: {}", e))?;
+/// Copy the selected `source` servers into `target` following `strategy`,
+/// reporting what happened to each. `recency` drives `prefer-newer`.
+fn apply_sync(
+    source: &McpServers,
+    target: &mut McpServers,
+    tools: ToolPair,
+    sidecar: &mut sidecar::Sidecar,
+    strategy: SyncStrategy,
+    recency: &Recency,
+    names: Option<&[String]>,
+) -> Vec<SyncItemResult> {
+    let result = |name: &str, action, diff, message: Option<&str>| SyncItemResult {
+        name: name.to_string(),
+        action,
+        diff,
+        report: None,
+        message: message.map(String::from),
+    };
+    let converted_result = |name: &str, action, diff, report: ConversionReport| SyncItemResult {
+        report: Some(report),
+        ..result(name, action, diff, None)
+    };
 
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse skills config: {}", e))
+    let selected: Vec<&String> = match names {
+        Some(names) => names.iter().collect(),
+        None => source.keys().collect(),
+    };
+    let mut items = Vec::new();
+    for name in selected {
+        let Some(config) = source.get(name) else {
+            items.push(result(name, SyncAction::Skipped, Vec::new(), Some("not in source")));
+            continue;
+        };
+        let (converted, report) = tools.convert_with_report(sidecar, name, config);
+        let errors = tools.validate(name, &converted);
+        if !errors.is_empty() {
+            items.push(result(name, SyncAction::Invalid, Vec::new(), Some(&errors.join("; "))));
+            continue;
+        }
+
+        let Some(existing) = target.get(name) else {
+            target.insert(name.clone(), converted);
+            items.push(converted_result(name, SyncAction::Added, Vec::new(), report));
+            continue;
+        };
+        let diff = diff::diff(existing, &converted);
+        let item = match strategy {
+            _ if diff.is_empty() => result(name, SyncAction::Unchanged, diff, None),
+            SyncStrategy::OnlyMissing => {
+                result(name, SyncAction::Skipped, diff, Some("already in target"))
+            }
+            SyncStrategy::SkipOnConflict => result(name, SyncAction::Conflict, diff, None),
+            SyncStrategy::PreferNewer => match recency.source_is_newer(name, config, existing) {
+                Some(true) => {
+                    target.insert(name.clone(), converted);
+                    converted_result(name, SyncAction::Updated, diff, report)
+                }
+                Some(false) => result(name, SyncAction::Skipped, diff, Some("target is newer")),
+                None => result(name, SyncAction::Conflict, diff, Some("cannot tell which side changed last")),
+            },
+            SyncStrategy::Overwrite => {
+                target.insert(name.clone(), converted);
+                converted_result(name, SyncAction::Updated, diff, report)
+            }
+        };
+        items.push(item);
+    }
+    items
 }
 
+/// A server that differs between two versions of a config: name, old and
+/// new entry (`None` when absent).
+type ServerChange = (String, Option<serde_json::Value>, Option<serde_json::Value>);
+
+fn changed_servers(before: &McpServers, after: &McpServers) -> Vec<ServerChange> {
+    let changed = after
+        .iter()
+        .filter(|(name, config)| before.get(*name) != Some(*config))
+        .map(|(name, config)| (name.clone(), before.get(name).cloned(), Some(config.clone())));
+    let removed = before
+        .iter()
+        .filter(|(name, _)| !after.contains_key(*name))
+        .map(|(name, config)| (name.clone(), Some(config.clone()), None));
+    changed.chain(removed).collect()
+}
+
+/// Sync user scope MCP servers from `direction.from` to `direction.to`:
+/// `names`, or every source server when `None`. Each file is loaded once and
+/// the target saved once; with `dry_run` nothing is written.
 #[tauri::command]
-fn save_skills_config(config: OhMyOpenCodeConfig) -> Result<(), String> {
-    let path = get_oh_my_opencode_path();
-    log::info!("Saving Skills config to: {:?}", path);
+fn sync_all(
+    direction: SyncDirection,
+    strategy: Option<SyncStrategy>,
+    names: Option<Vec<String>>,
+    dry_run: Option<bool>,
+) -> Result<SyncReport, ConfigError> {
+    let tools = direction.adapters()?;
+    let strategy = strategy.unwrap_or_default();
+    let dry_run = dry_run.unwrap_or(false);
+    log::info!(
+        "Syncing all MCPs from {} to {} with {:?}{}",
+        tools.from.label(),
+        tools.to.label(),
+        strategy,
+        if dry_run { " (dry run)" } else { "" }
+    );
+    let names = names.as_deref();
+
+    let meta_path = get_conversion_meta_path();
+    let mut meta = sidecar::load(&meta_path)?;
+    let source = tools.from.load_servers()?;
+    let base = merge::load(&get_sync_base_path())?;
+    let journal = match strategy {
+        SyncStrategy::PreferNewer => history::read(&get_history_path())?,
+        _ => Vec::new(),
+    };
+    let recency = Recency {
+        tools,
+        base: base.pairs.get(&tools.base_key()),
+        journal: &journal,
+    };
+    let mut run = |target: &mut McpServers| {
+        let before = target.clone();
+        let items = apply_sync(&source, target, tools, &mut meta, strategy, &recency, names);
+        (items, changed_servers(&before, target))
+    };
+    let (items, changes) = if dry_run {
+        run(&mut tools.to.load_servers()?)
+    } else {
+        let mut outcome = None;
+        tools.to.update_servers(&McpScope::User, true, &mut |servers| {
+            outcome = servers.map(&mut run);
+            Ok(None)
+        })?;
+        outcome.unwrap_or_default()
+    };
+    if !dry_run {
+        record_sync_base(tools, &mut meta, &source, &items)?;
+        sidecar::save(&meta_path, &meta)?;
+        for (name, before, after) in changes {
+            record_history(HistoryEntry::new(
+                HistoryAction::Sync,
+                tools.from.id(),
+                tools.to.id(),
+                &McpScope::User,
+                &name,
+                before,
+                after,
+            ));
+        }
+    }
+
+    Ok(SyncReport {
+        direction,
+        strategy,
+        dry_run,
+        items,
+    })
+}
+
+/// Remember both sides of every server a one-way sync left identical, so a
+/// later bidirectional sync knows they were in sync.
+fn record_sync_base(
+    tools: ToolPair,
+    sidecar: &mut sidecar::Sidecar,
+    source: &McpServers,
+    items: &[SyncItemResult],
+) -> Result<(), ConfigError> {
+    let path = get_sync_base_path();
+    let mut base = merge::load(&path)?;
+    let (_, reversed) = tools.ordered();
+    let pair = base.pairs.entry(tools.base_key()).or_default();
+    for item in items.iter().filter(|item| {
+        matches!(item.action, SyncAction::Added | SyncAction::Updated | SyncAction::Unchanged)
+    }) {
+        let Some(config) = source.get(&item.name) else {
+            continue;
+        };
+        let converted = tools.convert(sidecar, &item.name, config);
+        let (left, right) = if reversed {
+            (converted, config.clone())
+        } else {
+            (config.clone(), converted)
+        };
+        pair.insert(item.name.clone(), merge::BaseEntry { left, right });
+    }
+    merge::save(&path, &base)
+}
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+/// Converts between the tools of `tools` (left to right) through the sidecar
+/// so bidirectional sync is lossless too.
+struct MergeConverter<'a> {
+    tools: ToolPair,
+    sidecar: &'a mut sidecar::Sidecar,
+}
+
+impl merge::Converter for MergeConverter<'_> {
+    fn convert_to_right(
+        &mut self,
+        name: &str,
+        left: &serde_json::Value,
+    ) -> (serde_json::Value, ConversionReport) {
+        self.tools.convert_with_report(self.sidecar, name, left)
+    }
+
+    fn convert_to_left(
+        &mut self,
+        name: &str,
+        right: &serde_json::Value,
+    ) -> (serde_json::Value, ConversionReport) {
+        self.tools.reversed().convert_with_report(self.sidecar, name, right)
+    }
+
+    fn in_sync(&self, name: &str, left: &serde_json::Value, right: &serde_json::Value) -> bool {
+        let (user, ToolPair { from, to }) = (&McpScope::User, self.tools);
+        let plain = |from: &dyn ToolAdapter, to: &dyn ToolAdapter, config| {
+            adapter::convert(from, user, to, user, &mut sidecar::Sidecar::default(), name, config).0
+        };
+        self.sidecar.linked(name, (from.id(), user), left, (to.id(), user), right)
+            || plain(from, to, left) == *right
+            || plain(to, from, right) == *left
     }
 
-    let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize skills config: {}", e))?;
+    fn validate_right(&self, name: &str, config: &serde_json::Value) -> Vec<String> {
+        self.tools.to.validate(name, config)
+    }
+
+    fn validate_left(&self, name: &str, config: &serde_json::Value) -> Vec<String> {
+        self.tools.from.validate(name, config)
+    }
+}
+
+/// Sync the user scope MCP servers of the two tools of `direction` both ways
+/// (F06) with a three-way merge against the last synced state. One-sided
+/// additions, edits and deletions are applied to the other side; servers
+/// changed on both sides are only reported, with both versions. With
+/// `dry_run` nothing is written.
+#[tauri::command]
+fn sync_bidirectional(
+    direction: SyncDirection,
+    names: Option<Vec<String>>,
+    dry_run: Option<bool>,
+) -> Result<BidirectionalSyncReport, ConfigError> {
+    let (tools, _) = direction.adapters()?.ordered();
+    let (left, right) = (tools.from, tools.to);
+    let dry_run = dry_run.unwrap_or(false);
+    log::info!(
+        "Syncing MCPs between {} and {}{}",
+        left.label(),
+        right.label(),
+        if dry_run { " (dry run)" } else { "" }
+    );
+
+    let (left_servers, right_servers) = (left.load_servers()?, right.load_servers()?);
+    let base_path = get_sync_base_path();
+    let mut base = merge::load(&base_path)?;
+    let pair = base.pairs.entry(tools.base_key()).or_default();
+    let meta_path = get_conversion_meta_path();
+    let mut meta = sidecar::load(&meta_path)?;
+
+    let result = merge::merge(
+        &left_servers,
+        &right_servers,
+        pair,
+        names.as_deref(),
+        &mut MergeConverter {
+            tools,
+            sidecar: &mut meta,
+        },
+    );
+    if !dry_run {
+        // The merge saw the servers as loaded above: stop before writing
+        // anything over a server another program changed since
+        let touched: BTreeSet<&String> = result.left.iter().chain(&result.right).map(|(name, _)| name).collect();
+        check_unchanged(right, &right.load_servers()?, &right_servers, &touched)?;
+        write_merge(
+            &[
+                (left, &left_servers, &result.left),
+                (right, &right_servers, &result.right),
+            ],
+            &touched,
+        )?;
+        let journal = [
+            (right, left, &left_servers, &result.left),
+            (left, right, &right_servers, &result.right),
+        ];
+        for (from, to, before, changes) in journal {
+            for (name, after) in changes {
+                if after.is_none() {
+                    // Removed on both sides now
+                    meta.remove(name, (from.id(), &McpScope::User));
+                    meta.remove(name, (to.id(), &McpScope::User));
+                }
+                record_history(HistoryEntry::new(
+                    HistoryAction::Sync,
+                    from.id(),
+                    to.id(),
+                    &McpScope::User,
+                    name,
+                    before.get(name).cloned(),
+                    after.clone(),
+                ));
+            }
+        }
+        for (name, entry) in result.base {
+            match entry {
+                Some(entry) => pair.insert(name, entry),
+                None => pair.remove(&name),
+            };
+        }
+        merge::save(&base_path, &base)?;
+        sidecar::save(&meta_path, &meta)?;
+    }
 
-    fs::write(&path, content).map_err(|e| format!("Failed to write skills config: {}", e))?;
+    Ok(BidirectionalSyncReport {
+        left: left.id().to_string(),
+        right: right.id().to_string(),
+        dry_run,
+        items: result.items,
+    })
+}
 
+/// Write the merged changes of each side in turn. When a side fails, the
+/// sides already written are put back as they were loaded, so a sync is
+/// applied to both tools or to neither.
+fn write_merge(
+    sides: &[(&dyn ToolAdapter, &McpServers, &merge::Changes)],
+    touched: &BTreeSet<&String>,
+) -> Result<(), ConfigError> {
+    for (done, &(tool, loaded, changes)) in sides.iter().enumerate() {
+        let written = if changes.is_empty() {
+            tool.load_servers().and_then(|servers| check_unchanged(tool, &servers, loaded, touched))
+        } else {
+            tool.update_servers(&McpScope::User, true, &mut |servers| {
+                if let Some(servers) = servers {
+                    check_unchanged(tool, servers, loaded, touched)?;
+                    merge::apply(servers, changes);
+                }
+                Ok(None)
+            })
+            .map(|_| ())
+        };
+        if let Err(e) = written {
+            for &(tool, loaded, changes) in &sides[..done] {
+                if let Err(e) = revert_merge(tool, loaded, changes) {
+                    log::warn!("Could not roll back the sync in {}: {}", tool.label(), e);
+                }
+            }
+            return Err(e);
+        }
+    }
     Ok(())
 }
 
+/// Undo `changes` in `tool`, leaving alone servers changed again since.
+fn revert_merge(tool: &dyn ToolAdapter, loaded: &McpServers, changes: &merge::Changes) -> Result<(), ConfigError> {
+    tool.update_servers(&McpScope::User, true, &mut |servers| {
+        if let Some(servers) = servers {
+            for (name, after) in changes {
+                if servers.get(name) != after.as_ref() {
+                    continue;
+                }
+                match loaded.get(name) {
+                    Some(config) => servers.insert(name.clone(), config.clone()),
+                    None => servers.remove(name),
+                };
+            }
+        }
+        Ok(None)
+    })
+    .map(|_| ())
+}
+
+/// Fail with `ConfigError::Conflict` when one of `names` in `servers`, the
+/// current servers of `tool`, is not what it was in `loaded`.
+fn check_unchanged(
+    tool: &dyn ToolAdapter,
+    servers: &McpServers,
+    loaded: &McpServers,
+    names: &BTreeSet<&String>,
+) -> Result<(), ConfigError> {
+    match names.iter().find(|name| servers.get(**name) != loaded.get(**name)) {
+        Some(name) => {
+            log::warn!("MCP '{}' changed in {} during sync", name, tool.label());
+            Err(ConfigError::Conflict {
+                path: tool.user_path().to_string_lossy().to_string(),
+            })
+        }
+        None => Ok(()),
+    }
+}
+
+/// Bring an entry of any tool to one shape (the canonical one) so entries
+/// can be compared field by field. Every entry takes a round trip through
+/// the OpenCode format so that defaults (e.g. a missing `type`) are filled in
+/// the same way; fields only one tool knows about (`enabled`, `description`)
+/// drop out.
+fn normalize_mcp(tool: &dyn ToolAdapter, config: &serde_json::Value) -> serde_json::Value {
+    let opencode = adapter::OPENCODE;
+    let canonical = tool.to_canonical(config).0;
+    opencode.to_canonical(&opencode.to_native(&canonical).0).0
+}
+
+/// Compare every server name of `from` and `to`, the servers of the tools
+/// of `tools`, semantically.
+fn compare_servers(tools: ToolPair, from: &McpServers, to: &McpServers) -> Vec<ServerComparison> {
+    let names: BTreeSet<&String> = from.keys().chain(to.keys()).collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let (status, fields) = match (from.get(name), to.get(name)) {
+                (Some(f), Some(t)) => {
+                    let fields = diff::diff(&normalize_mcp(tools.from, f), &normalize_mcp(tools.to, t));
+                    let status = if fields.is_empty() {
+                        ComparisonStatus::Identical
+                    } else {
+                        ComparisonStatus::Differs
+                    };
+                    (status, fields)
+                }
+                (Some(_), None) => (ComparisonStatus::OnlyInFrom, Vec::new()),
+                _ => (ComparisonStatus::OnlyInTo, Vec::new()),
+            };
+            ServerComparison {
+                name: name.clone(),
+                status,
+                fields,
+            }
+        })
+        .collect()
+}
+
+/// Whether each same-named user scope server is the same in both tools of
+/// `direction`, ignoring the format differences between them.
+#[tauri::command]
+fn compare_mcp(direction: SyncDirection) -> Result<Vec<ServerComparison>, ConfigError> {
+    let tools = direction.adapters()?;
+    Ok(compare_servers(tools, &tools.from.load_servers()?, &tools.to.load_servers()?))
+}
+
+/// Preview syncing user scope MCP servers from `direction.from` to
+/// `direction.to`.
+#[tauri::command]
+fn preview_sync(
+    direction: SyncDirection,
+    names: Option<Vec<String>>,
+) -> Result<SyncDirectionPreview, ConfigError> {
+    let tools = direction.adapters()?;
+    log::info!("Previewing sync from {} to {}", tools.from.label(), tools.to.label());
+    let source = tools.from.load_servers()?;
+    let target = tools.to.load_servers()?;
+    // Used as-is for the preview, never saved.
+    let mut meta = sidecar::load(&get_conversion_meta_path())?;
+
+    Ok(preview_direction(&source, &target, tools, &mut meta, names.as_deref()))
+}
+
+// ============================================================================
+// History Commands
+// ============================================================================
+
+/// Journal entries, newest first; at most `limit` of them.
+#[tauri::command]
+fn get_sync_history(limit: Option<usize>) -> Result<Vec<HistoryEntry>, ConfigError> {
+    let mut entries = history::read(&get_history_path())?;
+    entries.reverse();
+    entries.truncate(limit.unwrap_or(usize::MAX));
+    Ok(entries)
+}
+
+/// Put the entry journaled as `id` back to its before-state and journal that
+/// as a new undo entry. Refuses when the entry changed again since, unless
+/// `force` is set.
+#[tauri::command]
+fn undo_history_entry(id: String, force: Option<bool>) -> Result<HistoryEntry, ConfigError> {
+    let entries = history::read(&get_history_path())?;
+    let undo = undo_entry(&entries, &id, force.unwrap_or(false), adapter::adapter)?;
+    record_history(undo.clone());
+    if undo.after.is_none() {
+        forget_conversion(&undo.name, &undo.target, &undo.scope);
+    }
+    Ok(undo)
+}
+
+/// Undo entry `id` of `entries` in the tool `tool_of` returns for its target,
+/// returning the undo entry to journal.
+fn undo_entry<'a>(
+    entries: &[HistoryEntry],
+    id: &str,
+    force: bool,
+    tool_of: impl Fn(&str) -> Result<&'a dyn ToolAdapter, ConfigError>,
+) -> Result<HistoryEntry, ConfigError> {
+    let entry = entries
+        .iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| ConfigError::InvalidInput(format!("No history entry with id {}", id)))?;
+    log::info!("Undoing {:?} of MCP '{}' in {}", entry.action, entry.name, entry.target);
+
+    let restore = |servers: &mut McpServers| {
+        let current = servers.get(&entry.name).cloned();
+        if current != entry.after && !force {
+            return Err(ConfigError::InvalidInput(format!(
+                "MCP '{}' changed after this history entry; undoing it would overwrite that change",
+                entry.name
+            )));
+        }
+        match &entry.before {
+            Some(config) => servers.insert(entry.name.clone(), config.clone()),
+            None => servers.remove(&entry.name),
+        };
+        Ok(current)
+    };
+    let tool = tool_of(&entry.target)?;
+    let mut update = |servers: Option<&mut McpServers>| match servers {
+        Some(servers) => restore(servers),
+        None => Err(ConfigError::mcp_not_found(&entry.name, &tool.scope_label(&entry.scope))),
+    };
+    let current = if entry.disabled {
+        tool.update_disabled(&entry.scope, &mut update)?
+    } else {
+        tool.update_servers(&entry.scope, true, &mut update)?
+    };
+
+    let mut undo = HistoryEntry::new(
+        HistoryAction::Undo,
+        &entry.target,
+        &entry.target,
+        &entry.scope,
+        &entry.name,
+        current,
+        entry.before.clone(),
+    );
+    undo.undoes = Some(entry.id.clone());
+    undo.disabled = entry.disabled;
+    Ok(undo)
+}
+
+// ============================================================================
+// Claude Settings Commands
+// ============================================================================
+
+/// The user settings file and, when `project` is given, the project's shared
+/// and local settings files, each with its path.
 #[tauri::command]
-fn add_skill(name: String, description: Option<String>, source: String) -> Result<(), String> {
-    let mut config = get_skills_config()?;
+fn get_claude_settings(project: Option<String>) -> Result<Vec<ClaudeSettingsFile>, ConfigError> {
+    let mut scopes = vec![SettingsScope::User];
+    if project.is_some() {
+        scopes.extend([SettingsScope::Project, SettingsScope::Local]);
+    }
+
+    scopes
+        .into_iter()
+        .map(|scope| {
+            let file = claude_settings_file(scope, project.as_deref())?;
+            Ok(ClaudeSettingsFile {
+                scope,
+                path: file.path.to_string_lossy().to_string(),
+                exists: file.path.exists(),
+                settings: file.load()?,
+            })
+        })
+        .collect()
+}
+
+/// Write `settings` to the file of `scope` and return its path.
+#[tauri::command]
+fn save_claude_settings(
+    scope: SettingsScope,
+    project: Option<String>,
+    settings: ClaudeSettings,
+) -> Result<String, ConfigError> {
+    let file = claude_settings_file(scope, project.as_deref())?;
+    log::info!("Saving Claude settings to {:?}", file.path);
+    file.update(|current| {
+        *current = settings.clone();
+        Ok(())
+    })?;
+    Ok(file.path.to_string_lossy().to_string())
+}
+
+// ============================================================================
+// Skills Commands
+// ============================================================================
+
+#[tauri::command]
+fn get_skills_config() -> Result<OhMyOpenCodeConfig, ConfigError> {
+    skills_config_file().load()
+}
 
+#[tauri::command]
+fn save_skills_config(config: OhMyOpenCodeConfig) -> Result<(), ConfigError> {
+    skills_config_file().save(&config)
+}
+
+#[tauri::command]
+fn add_skill(name: String, description: Option<String>, source: String) -> Result<(), ConfigError> {
     let skill = SkillConfig {
         name,
         description,
@@ -651,52 +2499,105 @@ fn add_skill(name: String, description: Option<String>, source: String) -> Resul
         source: Some(source),
     };
 
-    config.skills.push(skill);
-    save_skills_config(config)
+    update_skills_config(|config| {
+        config.skills.push(skill.clone());
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn remove_skill(name: String) -> Result<(), ConfigError> {
+    update_skills_config(|config| {
+        config.skills.retain(|s| s.name != name);
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn remove_skill(name: String) -> Result<(), String> {
-    let mut config = get_skills_config()?;
-    config.skills.retain(|s| s.name != name);
-    save_skills_config(config)
+fn toggle_skill(name: String, enabled: bool) -> Result<(), ConfigError> {
+    update_skills_config(|config| {
+        if let Some(skill) = config.skills.iter_mut().find(|s| s.name == name) {
+            skill.enabled = Some(enabled);
+        }
+        Ok(())
+    })
+}
+
+// ============================================================================
+// Project Commands
+// ============================================================================
+
+/// A project path as the frontend typed or picked it, in the form it is
+/// registered under: trimmed and without a trailing separator.
+fn normalize_project_path(path: &str) -> String {
+    path.trim().trim_end_matches(['/', '\\']).to_string()
 }
 
+/// Project directories whose `.mcp.json` is listed and edited.
 #[tauri::command]
-fn toggle_skill(name: String, enabled: bool) -> Result<(), String> {
-    let mut config = get_skills_config()?;
+fn get_projects() -> Vec<String> {
+    load_app_settings().projects
+}
 
-    if let Some(skill) = config.skills.iter_mut().find(|s| s.name == name) {
-        skill.enabled = Some(enabled);
+#[tauri::command]
+fn add_project(path: String) -> Result<Vec<String>, ConfigError> {
+    let path = normalize_project_path(&path);
+    // Validates that the directory exists
+    project_mcp_file(Path::new(&path))?;
+
+    let settings_path = get_app_settings_path();
+    let mut settings = app_settings::load(&settings_path)?;
+    if !settings.projects.contains(&path) {
+        log::info!("Registering project {}", path);
+        settings.projects.push(path);
+        app_settings::save(&settings_path, &settings)?;
     }
+    Ok(settings.projects)
+}
 
-    save_skills_config(config)
+/// Stop managing a project; its `.mcp.json` is left untouched.
+#[tauri::command]
+fn remove_project(path: String) -> Result<Vec<String>, ConfigError> {
+    let path = normalize_project_path(&path);
+    let settings_path = get_app_settings_path();
+    let mut settings = app_settings::load(&settings_path)?;
+    let before = settings.projects.len();
+    settings.projects.retain(|p| p != &path);
+    if settings.projects.len() != before {
+        log::info!("Unregistering project {}", path);
+        app_settings::save(&settings_path, &settings)?;
+    }
+    Ok(settings.projects)
 }
 
 // ============================================================================
 // Path Commands
 // ============================================================================
 
+/// Every config path, with where it came from (default, env or override).
 #[tauri::command]
-fn get_config_paths() -> HashMap<String, String> {
-    let mut paths = HashMap::new();
-    paths.insert(
-        "opencode".to_string(),
-        get_opencode_config_path().to_string_lossy().to_string(),
-    );
-    paths.insert(
-        "skills".to_string(),
-        get_oh_my_opencode_path().to_string_lossy().to_string(),
-    );
-    paths.insert(
-        "claude".to_string(),
-        get_claude_config_path().to_string_lossy().to_string(),
-    );
-    paths.insert(
-        "backup".to_string(),
-        get_backup_directory().to_string_lossy().to_string(),
-    );
-    paths
+fn get_config_paths() -> HashMap<String, ResolvedPath> {
+    path_resolver().all()
+}
+
+/// Persist a path override for `tool`; `None` or an empty path clears it.
+#[tauri::command]
+fn set_config_path_override(
+    tool: String,
+    path: Option<String>,
+) -> Result<HashMap<String, ResolvedPath>, ConfigError> {
+    let settings_path = get_app_settings_path();
+    let mut settings = app_settings::load(&settings_path)?;
+    let path = path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+
+    log::info!("Setting {} path override to {:?}", tool, path);
+    settings.path_overrides.set(&tool, path)?;
+    app_settings::save(&settings_path, &settings)?;
+
+    Ok(PathResolver::new(Environment::current(), settings.path_overrides).all())
 }
 
 // ============================================================================
@@ -704,12 +2605,11 @@ fn get_config_paths() -> HashMap<String, String> {
 // ============================================================================
 
 #[tauri::command]
-fn create_backup() -> Result<String, String> {
+fn create_backup() -> Result<String, ConfigError> {
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
     let backup_dir = get_backup_directory();
 
-    fs::create_dir_all(&backup_dir)
-        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    fs::create_dir_all(&backup_dir).map_err(|e| ConfigError::write(&backup_dir, e))?;
 
     // Load raw configs as JSON values
     let opencode_config = load_opencode_config()
@@ -729,16 +2629,16 @@ fn create_backup() -> Result<String, String> {
     };
 
     let backup_path = backup_dir.join(format!("backup_{}.json", timestamp));
-    let content = serde_json::to_string_pretty(&backup_data)
-        .map_err(|e| format!("Failed to serialize backup: {}", e))?;
+    let content = to_json_pretty(&backup_data)?;
 
-    fs::write(&backup_path, content).map_err(|e| format!("Failed to write backup: {}", e))?;
+    file_io::write_atomic_private(&backup_path, content.as_bytes())
+        .map_err(|e| ConfigError::write(&backup_path, e))?;
 
     Ok(backup_path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-fn list_backups() -> Result<Vec<String>, String> {
+fn list_backups() -> Result<Vec<String>, ConfigError> {
     let backup_dir = get_backup_directory();
 
     if !backup_dir.exists() {
@@ -746,8 +2646,7 @@ fn list_backups() -> Result<Vec<String>, String> {
     }
 
     let mut backups = Vec::new();
-    let entries =
-        fs::read_dir(&backup_dir).map_err(|e| format!("Failed to read backup directory: {}", e))?;
+    let entries = fs::read_dir(&backup_dir).map_err(|e| ConfigError::read(&backup_dir, e))?;
 
     for entry in entries.flatten() {
         if let Some(name) = entry.file_name().to_str() {
@@ -757,33 +2656,137 @@ fn list_backups() -> Result<Vec<String>, String> {
         }
     }
 
-    backups.sort_by(|a, b| b.cmp(a)); // Sort descending (newest first)
-    Ok(backups)
+    backups.sort_by(|a, b| b.cmp(a)); // Sort descending (newest first)
+    Ok(backups)
+}
+
+/// Restore a manual backup or an automatic snapshot.
+#[tauri::command]
+fn restore_backup(backup_path: String) -> Result<(), ConfigError> {
+    if snapshot::is_snapshot_file(Path::new(&backup_path)) {
+        return restore_snapshot(Path::new(&backup_path));
+    }
+
+    let backup_path = PathBuf::from(backup_path);
+    let content =
+        fs::read_to_string(&backup_path).map_err(|e| ConfigError::read(&backup_path, e))?;
+    let backup: BackupData = serde_json::from_str(&content)
+        .map_err(|e| ConfigError::parse(Some(&backup_path), &e))?;
+
+    if let Some(opencode_value) = backup.opencode_config {
+        if let Ok(opencode_config) = serde_json::from_value::<OpenCodeConfig>(opencode_value) {
+            save_opencode_config_internal(&opencode_config)?;
+        }
+    }
+
+    if let Some(claude_value) = backup.claude_config {
+        if let Ok(claude_config) = serde_json::from_value::<ClaudeConfig>(claude_value) {
+            save_claude_config_internal(&claude_config)?;
+        }
+    }
+
+    if let Some(skills_config) = backup.skills_config {
+        save_skills_config(skills_config)?;
+    }
+
+    Ok(())
+}
+
+/// Check that `path`, as recorded in a snapshot, is a config file of `source`.
+/// Project files are recognised by rebuilding them from their own directory.
+fn snapshot_target(source: &str, path: &Path) -> Result<PathBuf, ConfigError> {
+    let project_dir = |depth: usize| path.ancestors().nth(depth).filter(|dir| check_project_dir(dir).is_ok());
+    let known: Vec<PathBuf> = match source {
+        "opencode" => vec![opencode_config_file().path],
+        "claude" => vec![claude_config_file().path],
+        "claude-desktop" => vec![claude_desktop_config_file().path],
+        "skills" => vec![skills_config_file().path],
+        "project" => project_dir(1).map(|dir| dir.join(".mcp.json")).into_iter().collect(),
+        "cursor" => std::iter::once(cursor_config_file().path)
+            .chain(project_dir(2).map(|dir| dir.join(".cursor").join("mcp.json")))
+            .collect(),
+        "settings" => {
+            let project = project_dir(2).and_then(Path::to_str);
+            [SettingsScope::User, SettingsScope::Project, SettingsScope::Local]
+                .into_iter()
+                .filter_map(|scope| claude_settings_path(scope, project).ok())
+                .collect()
+        }
+        _ => {
+            return Err(ConfigError::InvalidInput(format!(
+                "Unknown snapshot source: {}",
+                source
+            )))
+        }
+    };
+
+    if known.iter().any(|file| file == path) {
+        Ok(path.to_path_buf())
+    } else {
+        Err(ConfigError::InvalidInput(format!(
+            "{} is not a {} config file, refusing to restore the snapshot there",
+            path.display(),
+            source
+        )))
+    }
+}
+
+/// Write an automatic snapshot back to the file it was taken from.
+fn restore_snapshot(snapshot_path: &Path) -> Result<(), ConfigError> {
+    let snapshot = snapshot::read_snapshot(snapshot_path)?;
+    log::info!("Restoring {} snapshot to {}", snapshot.source, snapshot.path);
+
+    let target = snapshot_target(&snapshot.source, Path::new(&snapshot.path))?;
+
+    // Restoring is itself a modification, so the current content is kept too.
+    let (current, fingerprint) =
+        file_io::read_with_fingerprint(&target).map_err(|e| ConfigError::read(&target, e))?;
+    if let Some(current) = current {
+        if current == snapshot.content {
+            return Ok(());
+        }
+        let retention = load_app_settings().snapshot_retention;
+        let backup_dir = get_backup_directory();
+        snapshot::take_snapshot(&backup_dir, &snapshot.source, &target, &current, &retention)
+            .map_err(|e| ConfigError::write(&backup_dir, e))?;
+    }
+
+    match file_io::write_atomic_if_unchanged(&target, snapshot.content.as_bytes(), &fingerprint)
+        .map_err(|e| ConfigError::write(&target, e))?
+    {
+        WriteOutcome::Written => Ok(()),
+        WriteOutcome::Conflict => Err(ConfigError::Conflict {
+            path: target.to_string_lossy().to_string(),
+        }),
+    }
+}
+
+/// List manual backups and automatic pre-write snapshots, newest first.
+#[tauri::command]
+fn list_snapshots() -> Result<Vec<SnapshotInfo>, ConfigError> {
+    let backup_dir = get_backup_directory();
+    snapshot::list(&backup_dir).map_err(|e| ConfigError::read(&backup_dir, e))
 }
 
 #[tauri::command]
-fn restore_backup(backup_path: String) -> Result<(), String> {
-    let content =
-        fs::read_to_string(&backup_path).map_err(|e| format!("Failed to read backup: {}", e))?;
-    let backup: BackupData =
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse backup: {}", e))?;
+fn get_snapshot_retention() -> SnapshotRetention {
+    load_app_settings().snapshot_retention
+}
 
-    if let Some(opencode_value) = backup.opencode_config {
-        if let Ok(opencode_config) = serde_json::from_value::<OpenCodeConfig>(opencode_value) {
-            save_opencode_config_internal(&opencode_config)?;
-        }
-    }
+#[tauri::command]
+fn set_snapshot_retention(retention: SnapshotRetention) -> Result<(), ConfigError> {
+    let path = get_app_settings_path();
+    let mut settings = app_settings::load(&path)?;
+    settings.snapshot_retention = retention;
+    app_settings::save(&path, &settings)?;
 
-    if let Some(claude_value) = backup.claude_config {
-        if let Ok(claude_config) = serde_json::from_value::<ClaudeConfig>(claude_value) {
-            save_claude_config_internal(&claude_config)?;
+    let backup_dir = get_backup_directory();
+    if backup_dir.exists() {
+        for source in SNAPSHOT_SOURCES {
+            snapshot::prune(&backup_dir, source, &settings.snapshot_retention, std::time::SystemTime::now())
+                .map_err(|e| ConfigError::write(&backup_dir, e))?;
         }
     }
-
-    if let Some(skills_config) = backup.skills_config {
-        save_skills_config(skills_config)?;
-    }
-
     Ok(())
 }
 
@@ -792,7 +2795,7 @@ fn restore_backup(backup_path: String) -> Result<(), String> {
 // ============================================================================
 
 #[tauri::command]
-fn export_mcp_config(export_path: String) -> Result<(), String> {
+fn export_mcp_config(export_path: String) -> Result<(), ConfigError> {
     let mcp_list = get_mcp_list()?;
 
     let export_data = serde_json::json!({
@@ -800,22 +2803,22 @@ fn export_mcp_config(export_path: String) -> Result<(), String> {
         "claude": mcp_list.claude
     });
 
-    let content = serde_json::to_string_pretty(&export_data)
-        .map_err(|e| format!("Failed to serialize export: {}", e))?;
+    let content = to_json_pretty(&export_data)?;
 
-    fs::write(&export_path, content).map_err(|e| format!("Failed to write export: {}", e))?;
+    let export_path = PathBuf::from(export_path);
+    write_atomic(&export_path, content.as_bytes()).map_err(|e| ConfigError::write(&export_path, e))?;
 
     Ok(())
 }
 
 #[tauri::command]
-fn export_skills_config(export_path: String) -> Result<(), String> {
+fn export_skills_config(export_path: String) -> Result<(), ConfigError> {
     let skills_config = get_skills_config()?;
 
-    let content = serde_json::to_string_pretty(&skills_config)
-        .map_err(|e| format!("Failed to serialize export: {}", e))?;
+    let content = to_json_pretty(&skills_config)?;
 
-    fs::write(&export_path, content).map_err(|e| format!("Failed to write export: {}", e))?;
+    let export_path = PathBuf::from(export_path);
+    write_atomic(&export_path, content.as_bytes()).map_err(|e| ConfigError::write(&export_path, e))?;
 
     Ok(())
 }
@@ -826,18 +2829,7 @@ fn export_skills_config(export_path: String) -> Result<(), String> {
 
 /// Get the MCP backup directory path: ~/.config/openclaude-tools/.openclaudesync
 fn get_mcp_backup_directory() -> PathBuf {
-    if cfg!(target_os = "windows") {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("OpenClaude-Tools")
-            .join(".openclaudesync")
-    } else {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".config")
-            .join("openclaude-tools")
-            .join(".openclaudesync")
-    }
+    get_app_config_directory().join(".openclaudesync")
 }
 
 /// MCP Backup Info
@@ -845,7 +2837,7 @@ fn get_mcp_backup_directory() -> PathBuf {
 pub struct MCPBackupInfo {
     pub filename: String,
     pub timestamp: String,
-    pub source: String, // "opencode" or "claude"
+    pub source: String, // Tool adapter id: "opencode", "claude", ...
     pub mcp_count: usize,
     pub path: String,
     pub created_at: String, // Human readable date
@@ -853,36 +2845,40 @@ pub struct MCPBackupInfo {
 
 /// Backup OpenCode MCP configurations (overwrites single file)
 #[tauri::command]
-fn backup_opencode_mcps() -> Result<MCPBackupInfo, String> {
+fn backup_opencode_mcps() -> Result<MCPBackupInfo, ConfigError> {
     log::info!("Starting OpenCode MCP backup...");
     backup_mcp_by_source("opencode")
 }
 
 /// Backup Claude Code MCP configurations (overwrites single file)
 #[tauri::command]
-fn backup_claude_mcps() -> Result<MCPBackupInfo, String> {
+fn backup_claude_mcps() -> Result<MCPBackupInfo, ConfigError> {
     log::info!("Starting Claude Code MCP backup...");
     backup_mcp_by_source("claude")
 }
 
+/// Backup the user scope MCPs of any tool (overwrites single file)
+#[tauri::command]
+fn backup_mcps(source: String) -> Result<MCPBackupInfo, ConfigError> {
+    log::info!("Starting {} MCP backup...", source);
+    backup_mcp_by_source(&source)
+}
+
 /// Internal function to backup MCP by source (single file per source)
-fn backup_mcp_by_source(source: &str) -> Result<MCPBackupInfo, String> {
+fn backup_mcp_by_source(source: &str) -> Result<MCPBackupInfo, ConfigError> {
+    let tool = adapter::adapter(source)?;
     let backup_dir = get_mcp_backup_directory();
-    fs::create_dir_all(&backup_dir)
-        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    fs::create_dir_all(&backup_dir).map_err(|e| ConfigError::write(&backup_dir, e))?;
     
     // Use fixed filename for each source (overwrite mode)
     let filename = format!("{}_mcps.json", source);
     
-    // Get MCP list
-    let mcp_list = get_mcp_list()?;
-    
-    // Filter MCPs by source
-    let mcps_to_backup = if source == "opencode" {
-        mcp_list.opencode
-    } else {
-        mcp_list.claude
-    };
+    // Only user-scope servers are backed up and restored
+    let mcps_to_backup: Vec<MCPItem> = tool
+        .list()?
+        .into_iter()
+        .filter(|mcp| mcp.scope == McpScope::User)
+        .collect();
     
     // Create backup data with MCP name as key
     let mut mcps: HashMap<String, serde_json::Value> = HashMap::new();
@@ -899,11 +2895,10 @@ fn backup_mcp_by_source(source: &str) -> Result<MCPBackupInfo, String> {
     });
     
     let backup_path = backup_dir.join(&filename);
-    let content = serde_json::to_string_pretty(&backup_data)
-        .map_err(|e| format!("Failed to serialize backup: {}", e))?;
+    let content = to_json_pretty(&backup_data)?;
     
-    fs::write(&backup_path, &content)
-        .map_err(|e| format!("Failed to write backup: {}", e))?;
+    file_io::write_atomic_private(&backup_path, content.as_bytes())
+        .map_err(|e| ConfigError::write(&backup_path, e))?;
     
     log::info!("MCP backup saved: {:?}", backup_path);
     
@@ -922,7 +2917,8 @@ fn backup_mcp_by_source(source: &str) -> Result<MCPBackupInfo, String> {
 
 /// Get single MCP backup info by source
 #[tauri::command]
-fn get_mcp_backup(source: String) -> Result<Option<MCPBackupInfo>, String> {
+fn get_mcp_backup(source: String) -> Result<Option<MCPBackupInfo>, ConfigError> {
+    adapter::adapter(&source)?;
     let backup_dir = get_mcp_backup_directory();
     let filename = format!("{}_mcps.json", source);
     let backup_path = backup_dir.join(&filename);
@@ -932,21 +2928,20 @@ fn get_mcp_backup(source: String) -> Result<Option<MCPBackupInfo>, String> {
     }
     
     // Read backup file to get MCP count
-    let content = fs::read_to_string(&backup_path)
-        .map_err(|e| format!("Failed to read backup: {}", e))?;
+    let content =
+        fs::read_to_string(&backup_path).map_err(|e| ConfigError::read(&backup_path, e))?;
     
     let backup_data: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse backup: {}", e))?;
+        .map_err(|e| ConfigError::parse(Some(&backup_path), &e))?;
     
     let mcp_count = backup_data["mcps"].as_object()
         .map(|m| m.len())
         .unwrap_or(0);
     
     // Get file metadata for timestamp
-    let metadata = fs::metadata(&backup_path)
-        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
-    let modified = metadata.modified()
-        .map_err(|e| format!("Failed to get modified time: {}", e))?;
+    let modified = fs::metadata(&backup_path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| ConfigError::read(&backup_path, e))?;
     let created_at = chrono::DateTime::<chrono::Local>::from(modified)
         .format("%Y-%m-%d %H:%M:%S")
         .to_string();
@@ -963,108 +2958,99 @@ fn get_mcp_backup(source: String) -> Result<Option<MCPBackupInfo>, String> {
 
 /// Restore all MCPs from backup
 #[tauri::command]
-fn restore_mcp_backup(source: String) -> Result<String, String> {
+fn restore_mcp_backup(source: String) -> Result<String, ConfigError> {
     log::info!("Restoring MCP backup for: {}", source);
+    adapter::adapter(&source)?;
     
     let backup_dir = get_mcp_backup_directory();
     let filename = format!("{}_mcps.json", source);
     let backup_path = backup_dir.join(&filename);
     
     if !backup_path.exists() {
-        return Err(format!("Backup file not found for {}", source));
+        return Err(ConfigError::FileNotFound {
+            path: backup_path.to_string_lossy().to_string(),
+        });
     }
     
-    let content = fs::read_to_string(&backup_path)
-        .map_err(|e| format!("Failed to read backup: {}", e))?;
+    let content =
+        fs::read_to_string(&backup_path).map_err(|e| ConfigError::read(&backup_path, e))?;
     
     let backup_data: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse backup: {}", e))?;
+        .map_err(|e| ConfigError::parse(Some(&backup_path), &e))?;
     
     let backup_source = backup_data["source"].as_str().unwrap_or("");
     let mcps = backup_data["mcps"].as_object()
-        .ok_or("Invalid backup format: missing mcps object")?;
+        .ok_or_else(|| ConfigError::InvalidInput("Invalid backup format: missing mcps object".to_string()))?;
     
-    let mut restored_count = 0;
+    let restored_count = mcps.len();
+    let tool = adapter::adapter(backup_source)?;
     
-    if backup_source == "opencode" {
-        let mut opencode_config = load_opencode_config()?;
-        for (name, config) in mcps {
-            opencode_config.mcp.insert(name.clone(), config.clone());
-            restored_count += 1;
-        }
-        save_opencode_config_internal(&opencode_config)?;
-    } else if backup_source == "claude" {
-        let mut claude_config = load_claude_config()?;
-        for (name, config) in mcps {
-            claude_config.mcp_servers.insert(name.clone(), config.clone());
-            restored_count += 1;
-        }
-        save_claude_config_internal(&claude_config)?;
-    } else {
-        return Err(format!("Unknown backup source: {}", backup_source));
-    }
+    tool.update_servers(&McpScope::User, true, &mut |servers| {
+        if let Some(servers) = servers {
+            for (name, config) in mcps {
+                servers.insert(name.clone(), config.clone());
+            }
+        }
+        Ok(None)
+    })?;
     
     log::info!("Restored {} MCPs from backup", restored_count);
-    Ok(format!("Successfully restored {} MCPs to {}", restored_count, 
-        if backup_source == "opencode" { "OpenCode" } else { "Claude Code" }))
+    Ok(format!("Successfully restored {} MCPs to {}", restored_count, tool.label()))
 }
 
 /// Read backup file content by source
 #[tauri::command]
-fn read_backup_content(source: String) -> Result<String, String> {
+fn read_backup_content(source: String) -> Result<String, ConfigError> {
+    adapter::adapter(&source)?;
     let backup_dir = get_mcp_backup_directory();
     let filename = format!("{}_mcps.json", source);
     let backup_path = backup_dir.join(&filename);
     
     if !backup_path.exists() {
-        return Err(format!("Backup file not found for {}", source));
+        return Err(ConfigError::FileNotFound {
+            path: backup_path.to_string_lossy().to_string(),
+        });
     }
     
-    fs::read_to_string(&backup_path)
-        .map_err(|e| format!("Failed to read backup: {}", e))
+    fs::read_to_string(&backup_path).map_err(|e| ConfigError::read(&backup_path, e))
 }
 
 /// Restore a single MCP from backup
 #[tauri::command]
-fn restore_single_mcp(source: String, mcp_name: String) -> Result<String, String> {
+fn restore_single_mcp(source: String, mcp_name: String) -> Result<String, ConfigError> {
     log::info!("Restoring single MCP '{}' from backup", mcp_name);
+    adapter::adapter(&source)?;
     
     let backup_dir = get_mcp_backup_directory();
     let filename = format!("{}_mcps.json", source);
     let backup_path = backup_dir.join(&filename);
     
     if !backup_path.exists() {
-        return Err(format!("Backup file not found for {}", source));
+        return Err(ConfigError::FileNotFound {
+            path: backup_path.to_string_lossy().to_string(),
+        });
     }
     
-    let content = fs::read_to_string(&backup_path)
-        .map_err(|e| format!("Failed to read backup: {}", e))?;
+    let content =
+        fs::read_to_string(&backup_path).map_err(|e| ConfigError::read(&backup_path, e))?;
     
     let backup_data: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse backup: {}", e))?;
+        .map_err(|e| ConfigError::parse(Some(&backup_path), &e))?;
     
     let backup_source = backup_data["source"].as_str().unwrap_or("");
     let mcps = backup_data["mcps"].as_object()
-        .ok_or("Invalid backup format: missing mcps object")?;
+        .ok_or_else(|| ConfigError::InvalidInput("Invalid backup format: missing mcps object".to_string()))?;
     
     let config = mcps.get(&mcp_name)
-        .ok_or_else(|| format!("MCP '{}' not found in backup", mcp_name))?;
+        .ok_or_else(|| ConfigError::mcp_not_found(&mcp_name, "backup"))?;
     
-    if backup_source == "opencode" {
-        let mut opencode_config = load_opencode_config()?;
-        opencode_config.mcp.insert(mcp_name.clone(), config.clone());
-        save_opencode_config_internal(&opencode_config)?;
-    } else if backup_source == "claude" {
-        let mut claude_config = load_claude_config()?;
-        claude_config.mcp_servers.insert(mcp_name.clone(), config.clone());
-        save_claude_config_internal(&claude_config)?;
-    } else {
-        return Err(format!("Unknown backup source: {}", backup_source));
-    }
+    let tool = adapter::adapter(backup_source)?;
+    tool.update_servers(&McpScope::User, true, &mut |servers| {
+        Ok(servers.and_then(|servers| servers.insert(mcp_name.clone(), config.clone())))
+    })?;
     
     log::info!("Restored MCP '{}' from backup", mcp_name);
-    Ok(format!("Successfully restored '{}' to {}", mcp_name, 
-        if backup_source == "opencode" { "OpenCode" } else { "Claude Code" }))
+    Ok(format!("Successfully restored '{}' to {}", mcp_name, tool.label()))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -1082,7 +3068,17 @@ pub fn run() {
             add_mcp,
             update_mcp,
             delete_mcp,
+            toggle_mcp,
+            preview_sync,
+            compare_mcp,
+            sync_all,
+            sync_bidirectional,
+            get_sync_history,
+            undo_history_entry,
             sync_mcp,
+            // Claude settings
+            get_claude_settings,
+            save_claude_settings,
             // Skills
             get_skills_config,
             save_skills_config,
@@ -1090,17 +3086,25 @@ pub fn run() {
             remove_skill,
             toggle_skill,
             // Paths
+            get_projects,
+            add_project,
+            remove_project,
             get_config_paths,
+            set_config_path_override,
             // Backup
             create_backup,
             list_backups,
             restore_backup,
+            list_snapshots,
+            get_snapshot_retention,
+            set_snapshot_retention,
             // Export
             export_mcp_config,
             export_skills_config,
             // MCP Backup (simplified)
             backup_opencode_mcps,
             backup_claude_mcps,
+            backup_mcps,
             get_mcp_backup,
             restore_mcp_backup,
             read_backup_content,
@@ -1118,6 +3122,15 @@ pub fn run() {
 mod tests {
     use super::*;
 
+    const TO_CLAUDE: ToolPair = ToolPair {
+        from: adapter::OPENCODE,
+        to: adapter::CLAUDE,
+    };
+    const TO_OPENCODE: ToolPair = ToolPair {
+        from: adapter::CLAUDE,
+        to: adapter::OPENCODE,
+    };
+
     #[test]
     fn test_opencode_to_claude_local_server() {
         let opencode_config = serde_json::json!({
@@ -1251,5 +3264,1034 @@ mod tests {
             serde_json::json!(["npx", "-y", "test-server"])
         );
         assert_eq!(back_to_opencode["environment"]["KEY"], "value");
+
+        // Through the sidecar a round trip is the identity, including fields
+        // Claude Code has no place for
+        let mut meta = sidecar::Sidecar::default();
+        let original = serde_json::json!({
+            "type": "local",
+            "command": ["npx", "-y", "test-server"],
+            "environment": { "KEY": "value" },
+            "enabled": false,
+            "description": "Test server",
+            "x-team": "infra"
+        });
+        let claude = TO_CLAUDE.convert(&mut meta, "test", &original);
+        let back = TO_OPENCODE.convert(&mut meta, "test", &claude);
+        assert_eq!(back, original);
+
+        // And the other way round
+        let original = serde_json::json!({
+            "type": "http",
+            "url": "https://api.example.com/mcp",
+            "headers": { "Authorization": "Bearer x" },
+            "oauth": { "clientId": "abc" }
+        });
+        let opencode = TO_OPENCODE.convert(&mut meta, "remote", &original);
+        let back = TO_CLAUDE.convert(&mut meta, "remote", &opencode);
+        assert_eq!(back, original);
+
+        // An edited Claude entry keeps the OpenCode-only fields
+        let edited = serde_json::json!({ "command": "npx", "args": ["-y", "test-server@2"] });
+        let back = TO_OPENCODE.convert(&mut meta, "test", &edited);
+        assert_eq!(back["command"], serde_json::json!(["npx", "-y", "test-server@2"]));
+        assert_eq!(back["enabled"], false);
+        assert_eq!(back["x-team"], "infra");
+    }
+
+    #[test]
+    fn test_transport_mapping() {
+        let stdio = serde_json::json!({ "type": "stdio", "command": "npx", "args": ["srv"] });
+        let sse = serde_json::json!({ "type": "sse", "url": "https://a.example/sse" });
+        let http = serde_json::json!({ "type": "http", "url": "https://a.example/mcp" });
+        let streamable = serde_json::json!({ "type": "streamable-http", "url": "https://a.example/mcp" });
+        let untyped_sse = serde_json::json!({ "url": "https://a.example/sse/?key=1" });
+        let untyped_local = serde_json::json!({ "command": "npx" });
+
+        // Claude -> OpenCode -> Claude, without any remembered metadata
+        for (claude, opencode_type, claude_type) in [
+            (&stdio, "local", "stdio"),
+            (&sse, "remote", "sse"),
+            (&http, "remote", "http"),
+            (&streamable, "remote", "http"),
+            (&untyped_sse, "remote", "sse"),
+            (&untyped_local, "local", "stdio"),
+        ] {
+            let opencode = convert_claude_to_opencode(claude);
+            assert_eq!(opencode["type"], opencode_type, "{}", claude);
+            assert_eq!(convert_opencode_to_claude(&opencode)["type"], claude_type, "{}", claude);
+        }
+
+        // OpenCode -> Claude
+        for (opencode, claude_type) in [
+            (serde_json::json!({ "type": "local", "command": ["npx"] }), "stdio"),
+            (serde_json::json!({ "type": "remote", "url": "https://a.example/mcp" }), "http"),
+            (serde_json::json!({ "type": "remote", "url": "https://a.example/sse" }), "sse"),
+            (serde_json::json!({ "type": "remote", "url": "https://a.example/mcp", "transport": "sse" }), "sse"),
+            (serde_json::json!({ "type": "remote", "url": "https://a.example/sse", "transport": "http" }), "http"),
+        ] {
+            assert_eq!(convert_opencode_to_claude(&opencode)["type"], claude_type, "{}", opencode);
+        }
+
+        // An SSE server the URL gives no hint about keeps its transport through
+        // the sidecar, also after being edited in OpenCode
+        let mut meta = sidecar::Sidecar::default();
+        let sse = serde_json::json!({ "type": "sse", "url": "https://a.example/events" });
+        let mut opencode = TO_OPENCODE.convert(&mut meta, "events", &sse);
+        assert_eq!(TO_CLAUDE.convert(&mut meta, "events", &opencode), sse);
+        opencode["url"] = serde_json::json!("https://b.example/events");
+        let claude = TO_CLAUDE.convert(&mut meta, "events", &opencode);
+        assert_eq!(claude["type"], "sse");
+        assert_eq!(claude["url"], "https://b.example/events");
+    }
+
+    #[test]
+    fn test_conversion_translates_variable_references() {
+        let opencode = serde_json::json!({
+            "type": "local",
+            "command": ["npx", "srv", "--root={env:HOME}/data"],
+            "environment": { "TOKEN": "{env:GH_TOKEN}", "KEY": "{file:~/.key}" },
+            "description": "{env:NOT_TRANSLATED}"
+        });
+        let (claude, report) = convert_opencode_to_claude_with_report(&opencode);
+        assert_eq!(claude["args"], serde_json::json!(["srv", "--root=${HOME}/data"]));
+        assert_eq!(claude["env"], serde_json::json!({ "TOKEN": "${GH_TOKEN}", "KEY": "{file:~/.key}" }));
+        assert_eq!(
+            report.warnings,
+            ["{file:~/.key} has no Claude Code equivalent and is kept as literal text"]
+        );
+
+        let claude = serde_json::json!({
+            "type": "http",
+            "url": "https://${HOST:-api.example.com}/mcp",
+            "headers": { "Authorization": "Bearer ${API_KEY}" }
+        });
+        let (opencode, report) = convert_claude_to_opencode_with_report(&claude);
+        assert_eq!(opencode["url"], "https://{env:HOST}/mcp");
+        assert_eq!(opencode["headers"]["Authorization"], "Bearer {env:API_KEY}");
+        assert_eq!(report.warnings.len(), 1);
+        assert!(convert_opencode_to_claude_with_report(&opencode).1.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_conversion_report_lists_dropped_renamed_and_inferred_fields() {
+        let rename = |from: &str, to: &str| FieldRename { from: from.into(), to: to.into() };
+        let opencode = serde_json::json!({
+            "type": "local",
+            "command": ["npx", "srv"],
+            "environment": { "A": "1" },
+            "enabled": false,
+            "description": "Server"
+        });
+        let (_, report) = convert_opencode_to_claude_with_report(&opencode);
+        assert_eq!(report.dropped, ["enabled", "description"]);
+        assert_eq!(report.renamed, [rename("command[1..]", "args"), rename("environment", "env")]);
+        assert!(report.inferred.is_empty());
+
+        let remote = serde_json::json!({ "type": "remote", "url": "https://a.example/mcp" });
+        assert_eq!(convert_opencode_to_claude_with_report(&remote).1.inferred, ["type"]);
+
+        let claude = serde_json::json!({ "url": "https://a.example/mcp", "oauth": { "clientId": "x" } });
+        let (_, report) = convert_claude_to_opencode_with_report(&claude);
+        assert_eq!(report.dropped, ["oauth"]);
+        assert_eq!(report.inferred, ["type", "enabled"]);
+        assert!(!report.is_empty());
+
+        // Converting back, the sidecar restores what the first conversion dropped
+        let mut meta = sidecar::Sidecar::default();
+        let claude = TO_CLAUDE.convert(&mut meta, "srv", &opencode);
+        let (back, report) = TO_OPENCODE.convert_with_report(&mut meta, "srv", &claude);
+        assert_eq!(back, opencode);
+        assert_eq!(report.restored, ["enabled", "description"]);
+        assert!(report.inferred.is_empty());
+    }
+
+    fn sample_opencode_document() -> serde_json::Value {
+        serde_json::json!({
+            "$schema": "https://opencode.ai/config.json",
+            "theme": "tokyonight",
+            "model": "anthropic/claude-sonnet-4",
+            "agent": {
+                "reviewer": {
+                    "description": "Reviews code",
+                    "prompt": "{file:./prompts/review.txt}",
+                    "tools": { "write": false }
+                }
+            },
+            "command": {
+                "test": { "template": "Run the tests", "agent": "build" }
+            },
+            "instructions": ["CONTRIBUTING.md"],
+            "permission": { "edit": "ask", "bash": { "git push": "ask" } },
+            "keybinds": { "leader": "ctrl+x" },
+            "lsp": { "rust": { "command": ["rust-analyzer"] } },
+            "formatter": { "prettier": { "disabled": true } },
+            "provider": {
+                "anthropic": { "options": { "timeout": 600000 } }
+            },
+            "plugin": ["opencode-notify"],
+            "mcp": {
+                "github": {
+                    "type": "local",
+                    "command": ["npx", "-y", "@modelcontextprotocol/server-github"],
+                    "enabled": true,
+                    "x-team-notes": { "owner": "infra" }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_opencode_config_roundtrip_preserves_unknown_keys() {
+        let original = sample_opencode_document();
+
+        let config: OpenCodeConfig = serde_json::from_value(original.clone()).unwrap();
+        let saved = serde_json::to_value(&config).unwrap();
+
+        assert_eq!(saved, original);
+    }
+
+    #[test]
+    fn test_opencode_config_mcp_edit_keeps_other_keys() {
+        let original = sample_opencode_document();
+
+        let mut config: OpenCodeConfig = serde_json::from_value(original.clone()).unwrap();
+        config.mcp.insert(
+            "fetch".to_string(),
+            serde_json::json!({ "type": "remote", "url": "https://example.com/mcp" }),
+        );
+        config.mcp.remove("github");
+        let saved = serde_json::to_value(&config).unwrap();
+
+        for key in [
+            "$schema", "theme", "model", "agent", "command", "instructions", "permission",
+            "keybinds", "lsp", "formatter", "provider", "plugin",
+        ] {
+            assert_eq!(saved[key], original[key], "key '{}' was not preserved", key);
+        }
+        assert_eq!(saved["mcp"]["fetch"]["url"], "https://example.com/mcp");
+        assert!(saved["mcp"].get("github").is_none());
+    }
+
+    #[test]
+    fn test_opencode_config_preserves_nested_mcp_fields_and_order() {
+        let content = r#"{
+            "theme": "system",
+            "mcp": {
+                "zeta": { "type": "local", "command": ["zeta"], "experimental": { "retries": 3 } },
+                "alpha": { "type": "remote", "url": "https://a.example", "oauth": false }
+            },
+            "agent": {}
+        }"#;
+
+        let config: OpenCodeConfig = serde_json::from_str(content).unwrap();
+        let saved = serde_json::to_string(&config).unwrap();
+        let reparsed: serde_json::Value = serde_json::from_str(&saved).unwrap();
+
+        assert_eq!(reparsed["mcp"]["zeta"]["experimental"]["retries"], 3);
+        assert_eq!(reparsed["mcp"]["alpha"]["oauth"], false);
+        let names: Vec<&String> = config.mcp.keys().collect();
+        assert_eq!(names, ["zeta", "alpha"]);
+    }
+
+    #[test]
+    fn test_render_opencode_config_keeps_jsonc_comments() {
+        let existing = r#"{
+  // personal theme
+  "theme": "system",
+  "mcp": {
+    "github": { "type": "local", "command": ["gh-mcp"] }, // keep me
+  },
+}
+"#;
+        let mut config: OpenCodeConfig = jsonc::parse(existing).unwrap();
+        config.mcp.insert(
+            "fetch".to_string(),
+            serde_json::json!({ "type": "remote", "url": "https://fetch.example" }),
+        );
+
+        let rendered = render_opencode_config(&config, Some(existing)).unwrap();
+
+        assert!(rendered.contains("// personal theme"));
+        assert!(rendered.contains("// keep me"));
+        let reparsed: OpenCodeConfig = jsonc::parse(&rendered).unwrap();
+        assert_eq!(reparsed.mcp["fetch"]["url"], "https://fetch.example");
+        assert_eq!(reparsed.other["theme"], "system");
+    }
+
+    fn test_claude_file(dir: &Path) -> ConfigFile<ClaudeConfig> {
+        ConfigFile {
+            source: "claude",
+            path: dir.join(".claude.json"),
+            snapshot_dir: Some(dir.join("backups")),
+            parse: parse_claude_config,
+            render: render_claude_config,
+        }
+    }
+
+    #[test]
+    fn test_claude_local_scope_servers_roundtrip() {
+        let original = r#"{
+  "mcpServers": { "global": { "command": "g" } },
+  "projects": {
+    "/work/app": {
+      "allowedTools": ["Bash"],
+      "history": [{ "display": "hi" }],
+      "mcpServers": { "db": { "command": "pg" } }
+    }
+  }
+}"#;
+        let mut config = parse_claude_config(original).unwrap();
+        let app = McpScope::Local { path: "/work/app".to_string() };
+        let other = McpScope::Local { path: "/work/other".to_string() };
+
+        assert!(config.servers_mut(&app).unwrap().contains_key("db"));
+        assert!(config.servers_mut(&other).is_none());
+        config
+            .servers_entry(&other)
+            .unwrap()
+            .insert("lint".to_string(), serde_json::json!({ "command": "eslint" }));
+        config.servers_mut(&app).unwrap().remove("db");
+
+        let rendered: serde_json::Value =
+            serde_json::from_str(&render_claude_config(&config, Some(original)).unwrap()).unwrap();
+        assert_eq!(rendered["mcpServers"]["global"]["command"], "g");
+        assert_eq!(rendered["projects"]["/work/app"]["mcpServers"], serde_json::json!({}));
+        assert_eq!(rendered["projects"]["/work/app"]["allowedTools"][0], "Bash");
+        assert_eq!(rendered["projects"]["/work/app"]["history"][0]["display"], "hi");
+        assert_eq!(rendered["projects"]["/work/other"]["mcpServers"]["lint"]["command"], "eslint");
+    }
+
+    #[test]
+    fn test_claude_config_keeps_key_order() {
+        let keys = |text: &str| -> Vec<String> {
+            let value: serde_json::Value = serde_json::from_str(text).unwrap();
+            value.as_object().unwrap().keys().cloned().collect()
+        };
+        let without_servers = r#"{
+  "numStartups": 3,
+  "env": { "ZED": "1", "DEBUG": true },
+  "projects": {
+    "/work/zeta": { "allowedTools": [] },
+    "/work/alpha": { "mcpServers": {} }
+  },
+  "autoUpdates": false,
+  "model": "opus"
+}"#;
+        let with_servers = r#"{
+  "theme": "dark",
+  "mcpServers": { "zeta": { "command": "z" } },
+  "env": { "B": "2", "A": "1" },
+  "autoUpdates": true
+}"#;
+
+        let mut config = parse_claude_config(without_servers).unwrap();
+        assert_eq!(config.env.as_ref().unwrap()["DEBUG"], true);
+        config
+            .servers_entry(&McpScope::Local { path: "/work/beta".to_string() })
+            .unwrap()
+            .insert("db".to_string(), serde_json::json!({ "command": "pg" }));
+        let rendered = render_claude_config(&config, Some(without_servers)).unwrap();
+
+        assert_eq!(keys(&rendered), keys(without_servers));
+        let saved: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        let projects: Vec<_> = saved["projects"].as_object().unwrap().keys().cloned().collect();
+        assert_eq!(projects, ["/work/zeta", "/work/alpha", "/work/beta"]);
+        assert!(saved["projects"]["/work/zeta"].get("mcpServers").is_none());
+        assert_eq!(saved["projects"]["/work/alpha"]["mcpServers"], serde_json::json!({}));
+        let env: Vec<_> = saved["env"].as_object().unwrap().keys().cloned().collect();
+        assert_eq!(env, ["ZED", "DEBUG"]);
+
+        let mut config = parse_claude_config(with_servers).unwrap();
+        config.servers_entry(&McpScope::User).unwrap().insert("alpha".to_string(), serde_json::json!({ "command": "a" }));
+        let rendered = render_claude_config(&config, Some(with_servers)).unwrap();
+
+        assert_eq!(keys(&rendered), ["theme", "mcpServers", "env", "autoUpdates"]);
+        let saved: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        let names: Vec<_> = saved["mcpServers"].as_object().unwrap().keys().cloned().collect();
+        assert_eq!(names, ["zeta", "alpha"]);
+        let env: Vec<_> = saved["env"].as_object().unwrap().keys().cloned().collect();
+        assert_eq!(env, ["B", "A"]);
+    }
+
+    #[test]
+    fn test_claude_settings_roundtrip_keeps_unknown_keys() {
+        let original = r#"{
+  "env": { "ZED": "1", "ANTHROPIC_LOG": "debug" },
+  "permissions": {
+    "allow": ["Bash(npm run test:*)"],
+    "defaultMode": "acceptEdits",
+    "futureFlag": true
+  },
+  "hooks": { "PostToolUse": [{ "matcher": "Edit", "hooks": [] }] },
+  "enabledMcpjsonServers": ["github"],
+  "statusLine": { "type": "command", "command": "~/.claude/status.sh" }
+}"#;
+        let mut settings = parse_claude_settings(original).unwrap();
+
+        assert_eq!(settings.enabled_mcpjson_servers.as_deref(), Some(&["github".to_string()][..]));
+        assert_eq!(settings.permissions.as_ref().unwrap().default_mode.as_deref(), Some("acceptEdits"));
+        settings.disabled_mcpjson_servers = Some(vec!["slack".to_string()]);
+
+        let rendered: serde_json::Value =
+            serde_json::from_str(&render_claude_settings(&settings, Some(original)).unwrap()).unwrap();
+        let mut expected: serde_json::Value = serde_json::from_str(original).unwrap();
+        expected["disabledMcpjsonServers"] = serde_json::json!(["slack"]);
+        assert_eq!(rendered, expected);
+        let env_keys: Vec<_> = settings.env.unwrap().keys().cloned().collect();
+        assert_eq!(env_keys, ["ZED", "ANTHROPIC_LOG"]);
+    }
+
+    #[test]
+    fn test_preview_direction_converts_before_comparing() {
+        let opencode = serde_json::json!({
+            "same": { "type": "local", "command": ["npx", "same"] },
+            "changed": { "type": "local", "command": ["npx", "changed@2"] },
+            "new": { "type": "remote", "url": "https://new" },
+        });
+        let claude = serde_json::json!({
+            "same": { "command": "npx", "args": ["same"], "type": "stdio" },
+            "changed": { "command": "npx", "args": ["changed@1"], "type": "stdio" },
+            "gone": { "command": "gone" },
+        });
+        let (opencode, claude) = (opencode.as_object().unwrap(), claude.as_object().unwrap());
+
+        let preview = preview_direction(opencode, claude, TO_CLAUDE, &mut sidecar::Sidecar::default(), None);
+
+        assert_eq!(preview.added, ["new"]);
+        assert_eq!(preview.removed, ["gone"]);
+        assert_eq!(preview.updated.len(), 1);
+        assert_eq!(preview.updated[0].name, "changed");
+        assert_eq!(
+            preview.updated[0].diff,
+            vec![FieldDiff {
+                path: "args[0]".into(),
+                old: Some(serde_json::json!("changed@1")),
+                new: Some(serde_json::json!("changed@2")),
+            }]
+        );
+
+        let only = ["gone".to_string()];
+        let preview = preview_direction(opencode, claude, TO_CLAUDE, &mut sidecar::Sidecar::default(), Some(&only));
+        assert!(preview.added.is_empty() && preview.updated.is_empty());
+        assert_eq!(preview.removed, ["gone"]);
+    }
+
+    #[test]
+    fn test_sync_direction_pairs_any_two_tools() {
+        let direction = |from: &str, to: &str| SyncDirection {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        assert!(direction("cursor", "cursor").adapters().is_err());
+        assert!(direction("cursor", "vscode").adapters().is_err());
+
+        let tools = direction("cursor", "opencode").adapters().unwrap();
+        let (ordered, reversed) = tools.ordered();
+        assert!(reversed);
+        assert_eq!((ordered.from.id(), ordered.to.id()), ("opencode", "cursor"));
+        // Both directions share one merge base, apart from other pairs
+        assert_eq!(tools.base_key(), tools.reversed().base_key());
+        assert_ne!(tools.base_key(), TO_CLAUDE.base_key());
+    }
+
+    #[test]
+    fn test_check_unchanged_only_looks_at_touched_servers() {
+        let loaded = serde_json::json!({ "a": { "command": "a" }, "b": { "command": "b" } });
+        let current = serde_json::json!({ "a": { "command": "a" }, "b": { "command": "b2" } });
+        let (loaded, current) = (loaded.as_object().unwrap(), current.as_object().unwrap());
+        let (a, b) = ("a".to_string(), "b".to_string());
+
+        assert!(check_unchanged(adapter::CLAUDE, current, loaded, &BTreeSet::from([&a])).is_ok());
+        assert!(matches!(
+            check_unchanged(adapter::CLAUDE, current, loaded, &BTreeSet::from([&a, &b])),
+            Err(ConfigError::Conflict { .. })
+        ));
+    }
+
+    /// A tool whose servers, live and disabled, live in memory.
+    #[derive(Default)]
+    struct MemoryTool(std::sync::Mutex<McpServers>, std::sync::Mutex<McpServers>);
+
+    impl ToolAdapter for MemoryTool {
+        fn id(&self) -> &'static str {
+            "memory"
+        }
+        fn label(&self) -> &'static str {
+            "Memory"
+        }
+        fn list(&self) -> Result<Vec<MCPItem>, ConfigError> {
+            Ok(Vec::new())
+        }
+        fn load_servers(&self) -> Result<McpServers, ConfigError> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+        fn user_path(&self) -> PathBuf {
+            PathBuf::from("memory.json")
+        }
+        fn update_servers(
+            &self,
+            _scope: &McpScope,
+            _create: bool,
+            mutate: &mut adapter::Mutate,
+        ) -> Result<Option<serde_json::Value>, ConfigError> {
+            mutate(Some(&mut self.0.lock().unwrap()))
+        }
+        fn update_disabled(
+            &self,
+            _scope: &McpScope,
+            mutate: &mut adapter::Mutate,
+        ) -> Result<Option<serde_json::Value>, ConfigError> {
+            mutate(Some(&mut self.1.lock().unwrap()))
+        }
+        fn validate(&self, _name: &str, _config: &serde_json::Value) -> Vec<String> {
+            Vec::new()
+        }
+        fn to_canonical(&self, config: &serde_json::Value) -> (serde_json::Value, ConversionReport) {
+            (config.clone(), ConversionReport::default())
+        }
+        fn to_native(&self, canonical: &serde_json::Value) -> (serde_json::Value, ConversionReport) {
+            (canonical.clone(), ConversionReport::default())
+        }
+        fn mapped_fields(&self) -> &'static [&'static str] {
+            &[]
+        }
+    }
+
+    #[test]
+    fn test_undo_restores_before_state_unless_changed_since() {
+        use serde_json::json;
+
+        let tool = MemoryTool::default();
+        let undo = |entry: &HistoryEntry, force| {
+            undo_entry(std::slice::from_ref(entry), &entry.id, force, |_| Ok(&tool as &dyn ToolAdapter))
+        };
+        let journal = |action, before, after| {
+            HistoryEntry::new(action, "memory", "memory", &McpScope::User, "gh", before, after)
+        };
+        let (v1, v2) = (json!({ "command": "gh" }), json!({ "command": "gh2" }));
+        let servers = || tool.load_servers().unwrap().get("gh").cloned();
+
+        // Add: undo deletes the server again
+        tool.0.lock().unwrap().insert("gh".to_string(), v1.clone());
+        let add = journal(HistoryAction::Add, None, Some(v1.clone()));
+        let undone = undo(&add, false).unwrap();
+        assert_eq!(servers(), None);
+        assert_eq!((undone.before, undone.after, undone.undoes), (Some(v1.clone()), None, Some(add.id)));
+
+        // Update: undo puts the old config back
+        tool.0.lock().unwrap().insert("gh".to_string(), v2.clone());
+        undo(&journal(HistoryAction::Update, Some(v1.clone()), Some(v2.clone())), false).unwrap();
+        assert_eq!(servers(), Some(v1.clone()));
+
+        // Delete: undo restores the server
+        tool.0.lock().unwrap().remove("gh");
+        undo(&journal(HistoryAction::Delete, Some(v1.clone()), None), false).unwrap();
+        assert_eq!(servers(), Some(v1.clone()));
+
+        // Changed since: refused unless forced
+        let v3 = json!({ "command": "gh3" });
+        tool.0.lock().unwrap().insert("gh".to_string(), v3.clone());
+        let update = journal(HistoryAction::Update, Some(v1.clone()), Some(v2));
+        assert!(matches!(undo(&update, false), Err(ConfigError::InvalidInput(_))));
+        assert_eq!(servers(), Some(v3.clone()));
+        let forced = undo(&update, true).unwrap();
+        assert_eq!(servers(), Some(v1));
+        assert_eq!(forced.before, Some(v3));
+
+        assert!(undo_entry(&[], "missing", false, |_| Ok(&tool as &dyn ToolAdapter)).is_err());
+    }
+
+    #[test]
+    fn test_apply_sync_strategies() {
+        let opencode = serde_json::json!({
+            "new": { "type": "remote", "url": "https://new" },
+            "changed": { "type": "local", "command": ["npx", "changed@2"] },
+            "broken": { "type": "local" },
+        });
+        let claude = serde_json::json!({
+            "changed": { "command": "npx", "args": ["changed@1"], "type": "stdio" },
+        });
+        let source = opencode.as_object().unwrap();
+        let no_recency = Recency { tools: TO_CLAUDE, base: None, journal: &[] };
+        let run = |strategy, recency: &Recency| {
+            let mut target = claude.as_object().unwrap().clone();
+            let mut meta = sidecar::Sidecar::default();
+            let items = apply_sync(source, &mut target, TO_CLAUDE, &mut meta, strategy, recency, None);
+            let actions: Vec<_> = items.iter().map(|i| (i.name.clone(), i.action)).collect();
+            (actions, target["changed"]["args"][0].clone())
+        };
+
+        let (actions, changed) = run(SyncStrategy::OnlyMissing, &no_recency);
+        assert_eq!(
+            actions,
+            [
+                ("new".to_string(), SyncAction::Added),
+                ("changed".to_string(), SyncAction::Skipped),
+                ("broken".to_string(), SyncAction::Invalid),
+            ]
+        );
+        assert_eq!(changed, "changed@1");
+        assert_eq!(run(SyncStrategy::Overwrite, &no_recency).1, "changed@2");
+        assert_eq!(run(SyncStrategy::SkipOnConflict, &no_recency).0[1].1, SyncAction::Conflict);
+        // Prefer-newer without anything to go on refuses to guess
+        let (actions, changed) = run(SyncStrategy::PreferNewer, &no_recency);
+        assert_eq!((actions[1].1, changed), (SyncAction::Conflict, serde_json::json!("changed@1")));
+    }
+
+    #[test]
+    fn test_prefer_newer_is_decided_per_server() {
+        use serde_json::json;
+
+        let opencode_v1 = json!({ "type": "local", "command": ["npx", "fetch@1"] });
+        let opencode_v2 = json!({ "type": "local", "command": ["npx", "fetch@2"] });
+        let claude_v1 = json!({ "command": "npx", "args": ["fetch@1"], "type": "stdio" });
+        let claude_v3 = json!({ "command": "npx", "args": ["fetch@3"], "type": "stdio" });
+        let base = |left: &serde_json::Value, right: &serde_json::Value| -> merge::PairBase {
+            [("fetch".to_string(), merge::BaseEntry { left: left.clone(), right: right.clone() })]
+                .into_iter()
+                .collect()
+        };
+        let written = |target: &str, after: &serde_json::Value, timestamp: &str| HistoryEntry {
+            timestamp: timestamp.to_string(),
+            ..HistoryEntry::new(HistoryAction::Update, target, target, &McpScope::User, "fetch", None, Some(after.clone()))
+        };
+
+        // Only one side changed since the last sync: that side is newer, in
+        // either direction and whatever the files' mtimes
+        let only_opencode = base(&opencode_v1, &claude_v1);
+        let recency = Recency { tools: TO_CLAUDE, base: Some(&only_opencode), journal: &[] };
+        assert_eq!(recency.source_is_newer("fetch", &opencode_v2, &claude_v1), Some(true));
+        let recency = Recency { tools: TO_OPENCODE, ..recency };
+        assert_eq!(recency.source_is_newer("fetch", &claude_v1, &opencode_v2), Some(false));
+
+        // Both changed: the journal dates each side's current version
+        let journal = [
+            written("opencode", &opencode_v2, "2026-10-01T10:00:00+02:00"),
+            written("claude", &claude_v3, "2026-10-02T10:00:00+02:00"),
+        ];
+        let recency = Recency { tools: TO_CLAUDE, base: Some(&only_opencode), journal: &journal };
+        assert_eq!(recency.source_is_newer("fetch", &opencode_v2, &claude_v3), Some(false));
+        let recency = Recency { tools: TO_OPENCODE, ..recency };
+        assert_eq!(recency.source_is_newer("fetch", &claude_v3, &opencode_v2), Some(true));
+
+        // A version the app did not write cannot be dated
+        let claude_v4 = json!({ "command": "npx", "args": ["fetch@4"], "type": "stdio" });
+        let recency = Recency { tools: TO_CLAUDE, ..recency };
+        assert_eq!(recency.source_is_newer("fetch", &opencode_v2, &claude_v4), None);
+    }
+
+    #[test]
+    fn test_compare_servers_ignores_format_differences() {
+        let opencode = serde_json::json!({
+            "github": {
+                "type": "local",
+                "command": ["npx", "-y", "github-mcp"],
+                "environment": { "TOKEN": "x" },
+                "enabled": true,
+                "description": "GitHub"
+            },
+            "fetch": { "type": "local", "command": ["uvx", "fetch@1"] },
+            "docs": { "type": "remote", "url": "https://docs" },
+        });
+        let claude = serde_json::json!({
+            "github": { "command": "npx", "args": ["-y", "github-mcp"], "env": { "TOKEN": "x" } },
+            "fetch": { "type": "stdio", "command": "uvx", "args": ["fetch@2"] },
+            "local-only": { "command": "x" },
+        });
+
+        let report = compare_servers(TO_CLAUDE, opencode.as_object().unwrap(), claude.as_object().unwrap());
+
+        let statuses: Vec<_> = report.iter().map(|c| (c.name.as_str(), c.status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                ("docs", ComparisonStatus::OnlyInFrom),
+                ("fetch", ComparisonStatus::Differs),
+                ("github", ComparisonStatus::Identical),
+                ("local-only", ComparisonStatus::OnlyInTo),
+            ]
+        );
+        assert_eq!(
+            report[1].fields,
+            vec![FieldDiff {
+                path: "args[0]".into(),
+                old: Some(serde_json::json!("fetch@1")),
+                new: Some(serde_json::json!("fetch@2")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_changed_servers_lists_additions_edits_and_removals() {
+        let before = serde_json::json!({ "kept": 1, "edited": 1, "removed": 1 });
+        let after = serde_json::json!({ "kept": 1, "edited": 2, "added": 1 });
+
+        assert_eq!(
+            changed_servers(before.as_object().unwrap(), after.as_object().unwrap()),
+            vec![
+                ("edited".to_string(), Some(serde_json::json!(1)), Some(serde_json::json!(2))),
+                ("added".to_string(), None, Some(serde_json::json!(1))),
+                ("removed".to_string(), Some(serde_json::json!(1)), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_listed_only_creates_list_when_adding() {
+        let mut list = None;
+        set_listed(&mut list, "github", false);
+        assert_eq!(list, None);
+
+        set_listed(&mut list, "github", true);
+        set_listed(&mut list, "slack", true);
+        set_listed(&mut list, "github", true);
+        assert_eq!(list, Some(vec!["slack".to_string(), "github".to_string()]));
+
+        set_listed(&mut list, "slack", false);
+        assert_eq!(list, Some(vec!["github".to_string()]));
+    }
+
+    #[test]
+    fn test_claude_settings_path_per_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().to_string_lossy().to_string();
+
+        assert_eq!(
+            claude_settings_path(SettingsScope::Project, Some(&project)).unwrap(),
+            dir.path().join(".claude/settings.json")
+        );
+        assert_eq!(
+            claude_settings_path(SettingsScope::Local, Some(&project)).unwrap(),
+            dir.path().join(".claude/settings.local.json")
+        );
+        assert_eq!(
+            claude_settings_path(SettingsScope::Local, None).unwrap_err().code(),
+            "invalid_input"
+        );
+    }
+
+    #[test]
+    fn test_project_mcp_config_keeps_order_and_unknown_keys() {
+        let original = r#"{
+  "mcpServers": {
+    "zeta": { "command": "z" },
+    "alpha": { "type": "http", "url": "https://a" }
+  },
+  "$comment": "shared servers"
+}"#;
+        let mut config = parse_project_mcp_config(original).unwrap();
+        config.mcp_servers.insert("beta".to_string(), serde_json::json!({ "command": "b" }));
+
+        let rendered = render_project_mcp_config(&config, Some(original)).unwrap();
+
+        let names: Vec<_> = config.mcp_servers.keys().cloned().collect();
+        assert_eq!(names, ["zeta", "alpha", "beta"]);
+        assert!(rendered.contains(r#""$comment": "shared servers""#));
+    }
+
+    #[test]
+    fn test_project_mcp_file_requires_existing_absolute_dir() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert_eq!(
+            project_mcp_file(Path::new("relative/project")).err().unwrap().code(),
+            "invalid_input"
+        );
+        assert_eq!(
+            project_mcp_file(&dir.path().join("missing")).err().unwrap().code(),
+            "file_not_found"
+        );
+        let file = project_mcp_file(dir.path()).unwrap();
+        assert_eq!(file.path, dir.path().join(".mcp.json"));
+    }
+
+    #[test]
+    fn test_write_merge_rolls_back_when_second_side_conflicts() {
+        use serde_json::json;
+
+        let (left, right) = (MemoryTool::default(), MemoryTool::default());
+        let left_loaded: McpServers = [("keep".to_string(), json!({ "command": "k" }))].into_iter().collect();
+        let right_loaded: McpServers = [("a".to_string(), json!({ "command": "a" }))].into_iter().collect();
+        *left.0.lock().unwrap() = left_loaded.clone();
+        *right.0.lock().unwrap() = right_loaded.clone();
+        let to_left = vec![("a".to_string(), Some(json!({ "type": "local", "command": ["a"] })))];
+        let to_right = vec![("b".to_string(), Some(json!({ "command": "b" })))];
+        let (a, b) = ("a".to_string(), "b".to_string());
+        let touched = BTreeSet::from([&a, &b]);
+
+        // Another program edits the right side after the merge loaded it
+        right.0.lock().unwrap().insert("a".to_string(), json!({ "command": "a2" }));
+        let result = write_merge(
+            &[
+                (&left as &dyn ToolAdapter, &left_loaded, &to_left),
+                (&right as &dyn ToolAdapter, &right_loaded, &to_right),
+            ],
+            &touched,
+        );
+
+        assert!(matches!(result, Err(ConfigError::Conflict { .. })));
+        assert_eq!(*left.0.lock().unwrap(), left_loaded);
+        assert_eq!(right.0.lock().unwrap()["a"], json!({ "command": "a2" }));
+        assert!(!right.0.lock().unwrap().contains_key("b"));
+    }
+
+    #[test]
+    fn test_edits_of_disabled_servers_are_journaled_and_undone() {
+        use serde_json::json;
+
+        let tool = MemoryTool::default();
+        let (v1, v2) = (json!({ "command": "gh" }), json!({ "command": "gh2" }));
+        tool.1.lock().unwrap().insert("gh".to_string(), v1.clone());
+        let disabled = || tool.1.lock().unwrap().get("gh").cloned();
+        let undo = |entry: &HistoryEntry| {
+            undo_entry(std::slice::from_ref(entry), &entry.id, false, |_| Ok(&tool as &dyn ToolAdapter))
+        };
+
+        let update = edit_server(&tool, HistoryAction::Update, "gh", &McpScope::User, Some(v2.clone())).unwrap();
+        assert!(update.disabled);
+        assert_eq!((update.before.clone(), update.after.clone()), (Some(v1.clone()), Some(v2.clone())));
+        assert_eq!(disabled(), Some(v2.clone()));
+
+        let delete = edit_server(&tool, HistoryAction::Delete, "gh", &McpScope::User, None).unwrap();
+        assert_eq!((delete.before.clone(), delete.after.clone()), (Some(v2.clone()), None));
+        assert_eq!(disabled(), None);
+
+        // Undo puts the server back among the disabled ones, not the live ones
+        let undone = undo(&delete).unwrap();
+        assert!(undone.disabled);
+        assert_eq!(disabled(), Some(v2));
+        assert!(tool.0.lock().unwrap().is_empty());
+        undo(&update).unwrap();
+        assert_eq!(disabled(), Some(v1));
+
+        assert!(matches!(
+            edit_server(&tool, HistoryAction::Delete, "missing", &McpScope::User, None),
+            Err(ConfigError::McpNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_update_disabled_servers_keeps_other_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("disabled-mcp.json");
+        let local = McpScope::Local { path: "/work/app".to_string() };
+        let mut store = disabled_mcp::DisabledStore::default();
+        store.insert("gh", &McpScope::User, serde_json::json!({ "command": "gh" }));
+        store.insert("gh", &local, serde_json::json!({ "command": "local-gh" }));
+        disabled_mcp::save(&path, &store).unwrap();
+
+        let removed = update_disabled_servers(&path, &McpScope::User, &mut |servers| {
+            Ok(servers.and_then(|servers| servers.remove("gh")))
+        })
+        .unwrap();
+
+        assert_eq!(removed, Some(serde_json::json!({ "command": "gh" })));
+        let store = disabled_mcp::load(&path).unwrap();
+        assert!(store.get("gh", &McpScope::User).is_none());
+        assert_eq!(store.get("gh", &local).unwrap().config["command"], "local-gh");
+    }
+
+    #[test]
+    fn test_toggle_claude_server_restores_exact_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = test_claude_file(dir.path());
+        let store_path = dir.path().join("disabled-mcp.json");
+        let original = serde_json::json!({
+            "mcpServers": {
+                "fetch": {
+                    "type": "stdio",
+                    "command": "npx",
+                    "args": ["-y", "fetch"],
+                    "env": { "TOKEN": "${TOKEN}" },
+                    "x-note": "kept"
+                }
+            },
+            "projects": {
+                "/work/app": {
+                    "allowedTools": ["Bash"],
+                    "mcpServers": { "db": { "type": "http", "url": "https://db", "headers": { "A": "1" } } }
+                }
+            },
+            "theme": "dark"
+        });
+        fs::write(&file.path, serde_json::to_string_pretty(&original).unwrap()).unwrap();
+        let read = || serde_json::from_str::<serde_json::Value>(&fs::read_to_string(&file.path).unwrap()).unwrap();
+
+        let local = McpScope::Local { path: "/work/app".to_string() };
+        for (name, scope, pointer) in [
+            ("fetch", McpScope::User, "/mcpServers/fetch"),
+            ("db", local, "/projects/~1work~1app/mcpServers/db"),
+        ] {
+            toggle_claude_server_in(&file, &store_path, name, &scope, false).unwrap();
+            assert!(read().pointer(pointer).is_none());
+            let stored = disabled_mcp::load(&store_path).unwrap();
+            assert_eq!(Some(&stored.get(name, &scope).unwrap().config), original.pointer(pointer));
+
+            toggle_claude_server_in(&file, &store_path, name, &scope, true).unwrap();
+            assert_eq!(read(), original);
+            assert!(disabled_mcp::load(&store_path).unwrap().get(name, &scope).is_none());
+        }
+    }
+
+    #[test]
+    fn test_toggle_project_server_round_trips_through_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().to_string_lossy().to_string();
+        let with_backups = |mut file: ConfigFile<ClaudeSettings>| {
+            file.snapshot_dir = Some(dir.path().join("backups"));
+            file
+        };
+        let mut mcp = project_mcp_file(dir.path()).unwrap();
+        mcp.snapshot_dir = Some(dir.path().join("backups"));
+        let local = with_backups(claude_settings_file(SettingsScope::Local, Some(&project)).unwrap());
+        let shared = with_backups(claude_settings_file(SettingsScope::Project, Some(&project)).unwrap());
+
+        let servers = r#"{
+  "mcpServers": {
+    "github": { "type": "http", "url": "https://api.github.com/mcp", "headers": { "Authorization": "Bearer ${GH}" } },
+    "slack": { "command": "slack-mcp" }
+  }
+}"#;
+        fs::write(&mcp.path, servers).unwrap();
+        fs::create_dir_all(dir.path().join(".claude")).unwrap();
+        fs::write(&shared.path, r#"{ "disabledMcpjsonServers": ["slack"] }"#).unwrap();
+
+        toggle_project_server_in(&mcp, &local, &shared, "github", false).unwrap();
+        assert_eq!(local.load().unwrap().disabled_mcpjson_servers, Some(vec!["github".to_string()]));
+        assert_eq!(project_disabled_servers(&project), ["slack", "github"]);
+
+        toggle_project_server_in(&mcp, &local, &shared, "github", true).unwrap();
+        assert_eq!(fs::read_to_string(&mcp.path).unwrap(), servers);
+        assert_eq!(project_disabled_servers(&project), ["slack"]);
+        assert_eq!(local.load().unwrap().enabled_mcpjson_servers, Some(vec!["github".to_string()]));
+
+        // Enabling also clears a listing in the shared settings
+        toggle_project_server_in(&mcp, &local, &shared, "slack", true).unwrap();
+        assert!(project_disabled_servers(&project).is_empty());
+        assert_eq!(fs::read_to_string(&mcp.path).unwrap(), servers);
+    }
+
+    #[test]
+    fn test_normalize_project_path() {
+        assert_eq!(normalize_project_path("  /work/app/ "), "/work/app");
+        assert_eq!(normalize_project_path("C:\\work\\app\\"), "C:\\work\\app");
+        assert_eq!(normalize_project_path("/work/app"), "/work/app");
+    }
+
+    #[test]
+    fn test_snapshot_target_only_accepts_config_files_of_the_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let code = |source: &str, path: &Path| snapshot_target(source, path).err().unwrap().code();
+
+        let mcp = dir.path().join(".mcp.json");
+        assert_eq!(snapshot_target("project", &mcp).unwrap(), mcp);
+        let cursor = dir.path().join(".cursor").join("mcp.json");
+        assert_eq!(snapshot_target("cursor", &cursor).unwrap(), cursor);
+        let local = dir.path().join(".claude").join("settings.local.json");
+        assert_eq!(snapshot_target("settings", &local).unwrap(), local);
+
+        assert_eq!(code("project", &dir.path().join(".bashrc")), "invalid_input");
+        assert_eq!(code("project", &cursor), "invalid_input");
+        assert_eq!(code("project", &dir.path().join("missing").join(".mcp.json")), "invalid_input");
+        assert_eq!(code("opencode", &mcp), "invalid_input");
+        assert_eq!(code("unknown", &mcp), "invalid_input");
+    }
+
+    #[test]
+    fn test_update_config_file_reapplies_mutation_after_external_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = test_claude_file(dir.path());
+        let path = file.path.clone();
+        fs::write(&path, r#"{ "numStartups": 1, "mcpServers": {} }"#).unwrap();
+
+        let mut calls = 0;
+        file.update(|config| {
+            calls += 1;
+            if calls == 1 {
+                // Claude Code rewrites the file while we are working on it.
+                fs::write(&path, r#"{ "numStartups": 2, "mcpServers": {} }"#).unwrap();
+            }
+            config
+                .mcp_servers
+                .insert("github".to_string(), serde_json::json!({ "command": "gh-mcp" }));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(calls, 2);
+        let saved: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["numStartups"], 2);
+        assert_eq!(saved["mcpServers"]["github"]["command"], "gh-mcp");
+        // Only the write that went through is snapshotted
+        let snapshots: Vec<_> = fs::read_dir(dir.path().join("backups")).unwrap().flatten().collect();
+        assert_eq!(snapshots.len(), 1);
+        let snapshot = snapshot::read_snapshot(&snapshots[0].path()).unwrap();
+        assert_eq!(snapshot.content, r#"{ "numStartups": 2, "mcpServers": {} }"#);
+    }
+
+    #[test]
+    fn test_update_config_file_reports_conflict_when_file_keeps_changing() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = test_claude_file(dir.path());
+        let path = file.path.clone();
+        fs::write(&path, "{}").unwrap();
+
+        let mut calls = 0;
+        let result = file.update(|config| {
+            calls += 1;
+            fs::write(&path, format!(r#"{{ "numStartups": {} }}"#, calls)).unwrap();
+            config.mcp_servers.insert("github".to_string(), serde_json::json!({ "command": "gh" }));
+            Ok(())
+        });
+
+        let err = result.unwrap_err();
+        assert_eq!(calls, MAX_WRITE_ATTEMPTS);
+        assert_eq!(err.code(), "conflict");
+        assert_eq!(err.path(), Some(path.to_string_lossy().as_ref()));
+        assert!(fs::read_to_string(&path).unwrap().contains(&calls.to_string()));
+        assert_eq!(fs::read_dir(dir.path().join("backups")).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_update_config_file_snapshots_previous_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = test_claude_file(dir.path());
+        let original = r#"{ "mcpServers": { "old": { "command": "old" } } }"#;
+        fs::write(&file.path, original).unwrap();
+
+        file.update(|config| {
+            config.mcp_servers.remove("old");
+            Ok(())
+        })
+        .unwrap();
+
+        let snapshots = snapshot::list(&dir.path().join("backups")).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].source.as_deref(), Some("claude"));
+        let saved = snapshot::read_snapshot(Path::new(&snapshots[0].path)).unwrap();
+        assert_eq!(saved.content, original);
+    }
+
+    #[test]
+    fn test_update_config_file_skips_unchanged_write_and_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = test_claude_file(dir.path());
+        let original = render_claude_config(&ClaudeConfig::default(), None).unwrap();
+        fs::write(&file.path, &original).unwrap();
+
+        file.update(|_| Ok(())).unwrap();
+
+        assert!(!dir.path().join("backups").exists());
+        assert_eq!(fs::read_to_string(&file.path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_opencode_config_does_not_add_missing_keys() {
+        let config: OpenCodeConfig = serde_json::from_str(r#"{ "theme": "system" }"#).unwrap();
+        let saved = serde_json::to_value(&config).unwrap();
+
+        assert_eq!(saved, serde_json::json!({ "theme": "system" }));
     }
 }
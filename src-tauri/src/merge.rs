@@ -0,0 +1,368 @@
+//! Three-way merge of the MCP servers of two tools.
+//!
+//! After every sync the app records what each server looked like on both sides
+//! (the merge base). Comparing each side with its base tells which side
+//! changed since then, so a bidirectional sync can copy one-sided edits,
+//! additions and deletions in either direction and only stop at servers that
+//! were changed differently on both sides.
+//!
+//! The base keeps each side in its own format, so a change is never inferred
+//! from a lossy conversion. Every pair of tools has its own base: a server
+//! synced between OpenCode and Cursor says nothing about Claude Code.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::ConfigError;
+use crate::file_io::write_atomic_private;
+use crate::ConversionReport;
+
+/// Both sides of a server as of the last sync.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BaseEntry {
+    #[serde(alias = "opencode")]
+    pub left: Value,
+    #[serde(alias = "claude")]
+    pub right: Value,
+}
+
+/// Server name → both sides, for one pair of tools.
+pub type PairBase = BTreeMap<String, BaseEntry>;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SyncBase {
+    /// `pair_key(left, right)` → the base of that pair.
+    pub pairs: BTreeMap<String, PairBase>,
+    /// The OpenCode and Claude Code base of files written before bases were
+    /// kept per pair; moved into `pairs` on load.
+    #[serde(skip_serializing)]
+    servers: PairBase,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// Key of the base of tools `left` and `right`.
+pub fn pair_key(left: &str, right: &str) -> String {
+    format!("{}+{}", left, right)
+}
+
+/// Load the merge base from `path`; a missing file means nothing was synced yet.
+pub fn load(path: &Path) -> Result<SyncBase, ConfigError> {
+    let mut base: SyncBase = match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| ConfigError::parse(Some(path), &e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => SyncBase::default(),
+        Err(e) => return Err(ConfigError::read(path, e)),
+    };
+    let legacy = std::mem::take(&mut base.servers);
+    if !legacy.is_empty() {
+        base.pairs.entry(pair_key("opencode", "claude")).or_default().extend(legacy);
+    }
+    Ok(base)
+}
+
+pub fn save(path: &Path, base: &SyncBase) -> Result<(), ConfigError> {
+    let content = serde_json::to_string_pretty(base).map_err(ConfigError::internal)?;
+    write_atomic_private(path, content.as_bytes()).map_err(|e| ConfigError::write(path, e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeOutcome {
+    Unchanged,
+    /// New or changed on the left only; copied to the right.
+    CopiedToRight,
+    /// New or changed on the right only; copied to the left.
+    CopiedToLeft,
+    /// Deleted on the left since the last sync; deleted on the right too.
+    DeletedFromRight,
+    /// Deleted on the right since the last sync; deleted on the left too.
+    DeletedFromLeft,
+    /// Changed differently on both sides; nothing was written.
+    Conflict,
+    /// The converted config would not load in the other tool.
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeItem {
+    pub name: String,
+    pub outcome: MergeOutcome,
+    /// For conflicts: both current versions and the base, to choose from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<BaseEntry>,
+    /// For copies, what the conversion changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<ConversionReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Entry name → new config, or `None` to delete it.
+pub type Changes = Vec<(String, Option<Value>)>;
+
+/// Result of [`merge`]: the report plus what to write where.
+#[derive(Debug, Default)]
+pub struct Merge {
+    pub items: Vec<MergeItem>,
+    pub left: Changes,
+    pub right: Changes,
+    /// New merge base entries, `None` to forget a server.
+    pub base: Vec<(String, Option<BaseEntry>)>,
+}
+
+/// Converts and validates entries between the formats of the two sides.
+pub trait Converter {
+    fn convert_to_right(&mut self, name: &str, left: &Value) -> (Value, ConversionReport);
+    fn convert_to_left(&mut self, name: &str, right: &Value) -> (Value, ConversionReport);
+    /// Whether both sides already hold the same server, in either format.
+    fn in_sync(&self, name: &str, left: &Value, right: &Value) -> bool;
+    fn validate_right(&self, name: &str, config: &Value) -> Vec<String>;
+    fn validate_left(&self, name: &str, config: &Value) -> Vec<String>;
+}
+
+/// Three-way merge of `names` (all servers of either side when `None`).
+pub fn merge(
+    left: &Map<String, Value>,
+    right: &Map<String, Value>,
+    base: &PairBase,
+    names: Option<&[String]>,
+    converter: &mut impl Converter,
+) -> Merge {
+    let names: BTreeSet<&String> = match names {
+        Some(names) => names.iter().collect(),
+        None => left.keys().chain(right.keys()).collect(),
+    };
+
+    let mut merge = Merge::default();
+    for name in names {
+        let (l, r, b) = (left.get(name), right.get(name), base.get(name));
+        // Without a base, a server present on one side only is new
+        let l_changed = b.is_none() || l != b.map(|b| &b.left);
+        let r_changed = b.is_none() || r != b.map(|b| &b.right);
+
+        match (l, r) {
+            (None, None) => {
+                if b.is_some() {
+                    merge.base.push((name.clone(), None));
+                }
+            }
+            (Some(l), Some(r)) if converter.in_sync(name, l, r) || (!l_changed && !r_changed) => {
+                let outcome = MergeOutcome::Unchanged;
+                merge.record(name, outcome, Some(l.clone()), Some(r.clone()));
+            }
+            (Some(l), _) if !r_changed => merge.copy_to_right(name, l, converter),
+            (_, Some(r)) if !l_changed => merge.copy_to_left(name, r, converter),
+            (Some(_), None) if !l_changed => {
+                merge.left.push((name.clone(), None));
+                merge.record(name, MergeOutcome::DeletedFromLeft, None, None);
+            }
+            (None, Some(_)) if !r_changed => {
+                merge.right.push((name.clone(), None));
+                merge.record(name, MergeOutcome::DeletedFromRight, None, None);
+            }
+            (Some(l), None) if b.is_none() => merge.copy_to_right(name, l, converter),
+            (None, Some(r)) if b.is_none() => merge.copy_to_left(name, r, converter),
+            // Changed on both sides, or changed on one and deleted on the other
+            (l, r) => merge.items.push(MergeItem {
+                name: name.clone(),
+                outcome: MergeOutcome::Conflict,
+                left: l.cloned(),
+                right: r.cloned(),
+                base: b.cloned(),
+                report: None,
+                message: None,
+            }),
+        }
+    }
+    merge
+}
+
+impl Merge {
+    /// Report `outcome` and remember the resulting pair as the new base
+    /// (forgetting the server when either side is gone).
+    fn record(&mut self, name: &str, outcome: MergeOutcome, left: Option<Value>, right: Option<Value>) {
+        let entry = left.zip(right).map(|(left, right)| BaseEntry { left, right });
+        self.base.push((name.to_string(), entry));
+        self.items.push(MergeItem {
+            name: name.to_string(),
+            outcome,
+            left: None,
+            right: None,
+            base: None,
+            report: None,
+            message: None,
+        });
+    }
+
+    fn invalid(&mut self, name: &str, errors: Vec<String>) {
+        self.items.push(MergeItem {
+            name: name.to_string(),
+            outcome: MergeOutcome::Invalid,
+            left: None,
+            right: None,
+            base: None,
+            report: None,
+            message: Some(errors.join("; ")),
+        });
+    }
+
+    fn copy_to_right(&mut self, name: &str, left: &Value, converter: &mut impl Converter) {
+        let (converted, report) = converter.convert_to_right(name, left);
+        let errors = converter.validate_right(name, &converted);
+        if !errors.is_empty() {
+            return self.invalid(name, errors);
+        }
+        self.right.push((name.to_string(), Some(converted.clone())));
+        self.record(name, MergeOutcome::CopiedToRight, Some(left.clone()), Some(converted));
+        self.attach_report(report);
+    }
+
+    fn copy_to_left(&mut self, name: &str, right: &Value, converter: &mut impl Converter) {
+        let (converted, report) = converter.convert_to_left(name, right);
+        let errors = converter.validate_left(name, &converted);
+        if !errors.is_empty() {
+            return self.invalid(name, errors);
+        }
+        self.left.push((name.to_string(), Some(converted.clone())));
+        self.record(name, MergeOutcome::CopiedToLeft, Some(converted), Some(right.clone()));
+        self.attach_report(report);
+    }
+
+    /// Attach `report` to the item recorded last.
+    fn attach_report(&mut self, report: ConversionReport) {
+        if let Some(item) = self.items.last_mut() {
+            item.report = Some(report);
+        }
+    }
+}
+
+/// Apply `changes` to the servers of one side.
+pub fn apply(servers: &mut Map<String, Value>, changes: &Changes) {
+    for (name, config) in changes {
+        match config {
+            Some(config) => servers.insert(name.clone(), config.clone()),
+            None => servers.remove(name),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Formats that only differ in the key used for the command: `cmd` on
+    /// the left, `command` on the right.
+    struct Rename;
+
+    impl Converter for Rename {
+        fn convert_to_right(&mut self, _: &str, left: &Value) -> (Value, ConversionReport) {
+            (json!({ "command": left["cmd"] }), ConversionReport::default())
+        }
+        fn convert_to_left(&mut self, _: &str, right: &Value) -> (Value, ConversionReport) {
+            (json!({ "cmd": right["command"] }), ConversionReport::default())
+        }
+        fn in_sync(&self, _: &str, left: &Value, right: &Value) -> bool {
+            left["cmd"] == right["command"]
+        }
+        fn validate_right(&self, _: &str, _: &Value) -> Vec<String> {
+            Vec::new()
+        }
+        fn validate_left(&self, _: &str, _: &Value) -> Vec<String> {
+            Vec::new()
+        }
+    }
+
+    fn base(entries: &[(&str, &str)]) -> PairBase {
+        entries
+            .iter()
+            .map(|(name, command)| {
+                let entry = BaseEntry {
+                    left: json!({ "cmd": command }),
+                    right: json!({ "command": command }),
+                };
+                (name.to_string(), entry)
+            })
+            .collect()
+    }
+
+    fn outcomes(merge: &Merge) -> Vec<(&str, MergeOutcome)> {
+        merge.items.iter().map(|i| (i.name.as_str(), i.outcome)).collect()
+    }
+
+    #[test]
+    fn test_merge_applies_one_sided_changes_both_ways() {
+        let base = base(&[("a", "a1"), ("b", "b1"), ("gone", "g"), ("same", "s")]);
+        let left = json!({ "a": { "cmd": "a2" }, "b": { "cmd": "b1" }, "new": { "cmd": "n" }, "same": { "cmd": "s" } });
+        let right = json!({ "a": { "command": "a1" }, "b": { "command": "b2" }, "gone": { "command": "g" }, "same": { "command": "s" } });
+
+        let merge = merge(left.as_object().unwrap(), right.as_object().unwrap(), &base, None, &mut Rename);
+
+        assert_eq!(
+            outcomes(&merge),
+            [
+                ("a", MergeOutcome::CopiedToRight),
+                ("b", MergeOutcome::CopiedToLeft),
+                ("gone", MergeOutcome::DeletedFromRight),
+                ("new", MergeOutcome::CopiedToRight),
+                ("same", MergeOutcome::Unchanged),
+            ]
+        );
+        assert_eq!(
+            merge.right,
+            [
+                ("a".to_string(), Some(json!({ "command": "a2" }))),
+                ("gone".to_string(), None),
+                ("new".to_string(), Some(json!({ "command": "n" }))),
+            ]
+        );
+        assert_eq!(merge.left, [("b".to_string(), Some(json!({ "cmd": "b2" })))]);
+        assert!(merge.base.contains(&("gone".to_string(), None)));
+    }
+
+    #[test]
+    fn test_merge_reports_conflicts_with_both_versions() {
+        let base = base(&[("a", "a1"), ("edited", "e1")]);
+        let left = json!({ "a": { "cmd": "a2" }, "unsynced": { "cmd": "u1" } });
+        let right = json!({ "a": { "command": "a3" }, "edited": { "command": "e2" }, "unsynced": { "command": "u2" } });
+
+        let merge = merge(left.as_object().unwrap(), right.as_object().unwrap(), &base, None, &mut Rename);
+
+        assert_eq!(
+            outcomes(&merge),
+            [
+                ("a", MergeOutcome::Conflict),
+                ("edited", MergeOutcome::Conflict),
+                ("unsynced", MergeOutcome::Conflict),
+            ]
+        );
+        let conflict = &merge.items[0];
+        assert_eq!(conflict.left, Some(json!({ "cmd": "a2" })));
+        assert_eq!(conflict.right, Some(json!({ "command": "a3" })));
+        assert_eq!(conflict.base.as_ref().unwrap().right, json!({ "command": "a1" }));
+        // Deleted on the left, changed on the right
+        assert_eq!(merge.items[1].left, None);
+        assert!(merge.left.is_empty() && merge.right.is_empty() && merge.base.is_empty());
+    }
+
+    #[test]
+    fn test_load_moves_legacy_base_into_its_pair() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sync-base.json");
+        fs::write(&path, r#"{ "servers": { "a": { "opencode": { "cmd": "a1" }, "claude": { "command": "a1" } } } }"#).unwrap();
+
+        let loaded = load(&path).unwrap();
+        assert_eq!(loaded.pairs[&pair_key("opencode", "claude")], base(&[("a", "a1")]));
+        save(&path, &loaded).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("servers"));
+    }
+}
@@ -0,0 +1,454 @@
+//! Where the config files of each tool live.
+//!
+//! Every path is resolved in the same order: a per-tool override saved in the
+//! app settings, then the tool's own environment variables, then the platform
+//! default. The origin is reported alongside the path so the UI can show why a
+//! location was picked.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::ConfigError;
+
+/// File names OpenCode reads its global config from, in order of preference.
+const OPENCODE_CONFIG_FILE_NAMES: [&str; 2] = ["opencode.jsonc", "opencode.json"];
+
+/// Environment variables that influence path resolution.
+const ENV_VARS: [&str; 4] = [
+    "XDG_CONFIG_HOME",
+    "OPENCODE_CONFIG",
+    "OPENCODE_CONFIG_DIR",
+    "CLAUDE_CONFIG_DIR",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathOrigin {
+    Default,
+    Env,
+    Override,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedPath {
+    pub path: PathBuf,
+    pub origin: PathOrigin,
+    /// The environment variable the path came from, when `origin` is `env`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable: Option<String>,
+}
+
+impl ResolvedPath {
+    fn new(path: PathBuf, origin: PathOrigin) -> Self {
+        Self {
+            path,
+            origin,
+            variable: None,
+        }
+    }
+
+    fn env(path: PathBuf, variable: &str) -> Self {
+        Self {
+            path,
+            origin: PathOrigin::Env,
+            variable: Some(variable.to_string()),
+        }
+    }
+
+    /// A path below this one, keeping the origin.
+    fn join(&self, name: &str) -> Self {
+        Self {
+            path: self.path.join(name),
+            origin: self.origin,
+            variable: self.variable.clone(),
+        }
+    }
+}
+
+/// User-chosen locations, persisted in the app settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathOverrides {
+    /// OpenCode config file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opencode: Option<PathBuf>,
+    /// oh-my-opencode.json.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skills: Option<PathBuf>,
+    /// Claude Code's `.claude.json`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude: Option<PathBuf>,
+    /// Claude Desktop's `claude_desktop_config.json`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_desktop: Option<PathBuf>,
+    /// Cursor's global `mcp.json`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<PathBuf>,
+    /// Directory for backups and snapshots.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+}
+
+impl PathOverrides {
+    /// Set or clear (`None`) the override for `tool`.
+    pub fn set(&mut self, tool: &str, path: Option<PathBuf>) -> Result<(), ConfigError> {
+        if let Some(path) = &path {
+            if !path.is_absolute() {
+                return Err(ConfigError::InvalidInput(format!(
+                    "Path override must be absolute: {}",
+                    path.display()
+                )));
+            }
+        }
+        let slot = match tool {
+            "opencode" => &mut self.opencode,
+            "skills" => &mut self.skills,
+            "claude" => &mut self.claude,
+            "claude-desktop" => &mut self.claude_desktop,
+            "cursor" => &mut self.cursor,
+            "backup" => &mut self.backup,
+            _ => {
+                return Err(ConfigError::InvalidInput(format!(
+                    "Unknown config path: {}",
+                    tool
+                )))
+            }
+        };
+        *slot = path;
+        Ok(())
+    }
+}
+
+/// The parts of the process environment that paths are derived from.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    home: PathBuf,
+    /// Platform config directory: `%APPDATA%`, `~/Library/Application Support`
+    /// or the XDG config home.
+    config_dir: PathBuf,
+    windows: bool,
+    linux: bool,
+    vars: HashMap<String, String>,
+}
+
+impl Environment {
+    pub fn current() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        Self {
+            config_dir: dirs::config_dir().unwrap_or_else(|| home.clone()),
+            home,
+            windows: cfg!(target_os = "windows"),
+            linux: cfg!(target_os = "linux"),
+            vars: ENV_VARS
+                .iter()
+                .filter_map(|name| std::env::var(name).ok().map(|value| (name.to_string(), value)))
+                .collect(),
+        }
+    }
+
+    /// A path-valued variable; unset and empty are treated the same.
+    fn var(&self, name: &str) -> Option<PathBuf> {
+        self.vars
+            .get(name)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// `$XDG_CONFIG_HOME`, or the platform equivalent.
+    fn config_home(&self) -> ResolvedPath {
+        match self.var("XDG_CONFIG_HOME") {
+            Some(dir) => ResolvedPath::env(dir, "XDG_CONFIG_HOME"),
+            None if self.windows => ResolvedPath::new(self.config_dir.clone(), PathOrigin::Default),
+            None => ResolvedPath::new(self.home.join(".config"), PathOrigin::Default),
+        }
+    }
+
+    /// Where desktop apps keep their settings: the XDG config home on Linux,
+    /// the platform config directory elsewhere.
+    fn app_data_home(&self) -> ResolvedPath {
+        if self.linux {
+            self.config_home()
+        } else {
+            ResolvedPath::new(self.config_dir.clone(), PathOrigin::Default)
+        }
+    }
+
+    /// Directory holding the app's own settings, backups and snapshots.
+    ///
+    /// Not overridable: the overrides themselves are stored there.
+    pub fn app_config_dir(&self) -> PathBuf {
+        if self.windows && self.var("XDG_CONFIG_HOME").is_none() {
+            self.config_dir.join("OpenClaude-Tools")
+        } else {
+            self.config_home().path.join("openclaude-tools")
+        }
+    }
+}
+
+/// Resolves every config location from the environment and saved overrides.
+pub struct PathResolver {
+    env: Environment,
+    overrides: PathOverrides,
+}
+
+impl PathResolver {
+    pub fn new(env: Environment, overrides: PathOverrides) -> Self {
+        Self { env, overrides }
+    }
+
+    fn overridden(path: &Option<PathBuf>) -> Option<ResolvedPath> {
+        path.clone().map(|path| ResolvedPath::new(path, PathOrigin::Override))
+    }
+
+    /// OpenCode's global config directory.
+    pub fn opencode_config_dir(&self) -> ResolvedPath {
+        match self.env.var("OPENCODE_CONFIG_DIR") {
+            Some(dir) => ResolvedPath::env(dir, "OPENCODE_CONFIG_DIR"),
+            None => self.env.config_home().join("opencode"),
+        }
+    }
+
+    /// The OpenCode config file: the override, `$OPENCODE_CONFIG`, or the first
+    /// existing `opencode.jsonc`/`opencode.json` in the config directory.
+    pub fn opencode_config(&self) -> ResolvedPath {
+        if let Some(path) = Self::overridden(&self.overrides.opencode) {
+            return path;
+        }
+        if let Some(path) = self.env.var("OPENCODE_CONFIG") {
+            return ResolvedPath::env(path, "OPENCODE_CONFIG");
+        }
+        let dir = self.opencode_config_dir();
+        ResolvedPath {
+            path: find_opencode_config(&dir.path),
+            ..dir
+        }
+    }
+
+    pub fn skills_config(&self) -> ResolvedPath {
+        Self::overridden(&self.overrides.skills)
+            .unwrap_or_else(|| self.opencode_config_dir().join("oh-my-opencode.json"))
+    }
+
+    /// Claude Code's config directory (`~/.claude`, or `$CLAUDE_CONFIG_DIR`).
+    pub fn claude_config_dir(&self) -> ResolvedPath {
+        match self.env.var("CLAUDE_CONFIG_DIR") {
+            Some(dir) => ResolvedPath::env(dir, "CLAUDE_CONFIG_DIR"),
+            None => ResolvedPath::new(self.env.home.join(".claude"), PathOrigin::Default),
+        }
+    }
+
+    /// `.claude.json`: next to the home directory by default, inside
+    /// `$CLAUDE_CONFIG_DIR` when that is set.
+    pub fn claude_config(&self) -> ResolvedPath {
+        if let Some(path) = Self::overridden(&self.overrides.claude) {
+            return path;
+        }
+        match self.env.var("CLAUDE_CONFIG_DIR") {
+            Some(dir) => ResolvedPath::env(dir.join(".claude.json"), "CLAUDE_CONFIG_DIR"),
+            None => ResolvedPath::new(self.env.home.join(".claude.json"), PathOrigin::Default),
+        }
+    }
+
+    /// Claude Desktop's `claude_desktop_config.json`, in its `Claude` settings
+    /// directory (`~/.config/Claude` on Linux).
+    pub fn claude_desktop_config(&self) -> ResolvedPath {
+        Self::overridden(&self.overrides.claude_desktop).unwrap_or_else(|| {
+            self.env
+                .app_data_home()
+                .join("Claude")
+                .join("claude_desktop_config.json")
+        })
+    }
+
+    /// Cursor's global `~/.cursor/mcp.json`. Projects have their own
+    /// `.cursor/mcp.json`, which is not resolved here.
+    pub fn cursor_config(&self) -> ResolvedPath {
+        Self::overridden(&self.overrides.cursor).unwrap_or_else(|| {
+            ResolvedPath::new(self.env.home.join(".cursor").join("mcp.json"), PathOrigin::Default)
+        })
+    }
+
+    pub fn backup_dir(&self) -> ResolvedPath {
+        if let Some(path) = Self::overridden(&self.overrides.backup) {
+            return path;
+        }
+        let path = self.env.app_config_dir().join("backups");
+        match self.env.var("XDG_CONFIG_HOME") {
+            Some(_) => ResolvedPath::env(path, "XDG_CONFIG_HOME"),
+            None => ResolvedPath::new(path, PathOrigin::Default),
+        }
+    }
+
+    /// All user-facing paths, keyed like the overrides.
+    pub fn all(&self) -> HashMap<String, ResolvedPath> {
+        HashMap::from([
+            ("opencode".to_string(), self.opencode_config()),
+            ("skills".to_string(), self.skills_config()),
+            ("claude".to_string(), self.claude_config()),
+            ("claude-desktop".to_string(), self.claude_desktop_config()),
+            ("cursor".to_string(), self.cursor_config()),
+            ("backup".to_string(), self.backup_dir()),
+        ])
+    }
+}
+
+/// The first existing OpenCode config file in `dir`, falling back to
+/// `opencode.json` for a fresh install.
+fn find_opencode_config(dir: &Path) -> PathBuf {
+    let existing: Vec<PathBuf> = OPENCODE_CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.exists())
+        .collect();
+
+    if existing.len() > 1 {
+        log::warn!(
+            "Both {:?} and {:?} exist; editing {:?}",
+            existing[0],
+            existing[1],
+            existing[0]
+        );
+    }
+
+    existing
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| dir.join("opencode.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_env(home: &Path, vars: &[(&str, &str)]) -> Environment {
+        Environment {
+            home: home.to_path_buf(),
+            config_dir: home.join("AppData"),
+            windows: false,
+            linux: true,
+            vars: vars
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_defaults_without_environment() {
+        let home = Path::new("/home/u");
+        let resolver = PathResolver::new(test_env(home, &[]), PathOverrides::default());
+
+        let opencode = resolver.opencode_config();
+        assert_eq!(opencode.path, home.join(".config/opencode/opencode.json"));
+        assert_eq!(opencode.origin, PathOrigin::Default);
+        assert_eq!(resolver.claude_config().path, home.join(".claude.json"));
+        assert_eq!(
+            resolver.claude_desktop_config().path,
+            home.join(".config/Claude/claude_desktop_config.json")
+        );
+        assert_eq!(resolver.cursor_config().path, home.join(".cursor/mcp.json"));
+        assert_eq!(
+            resolver.backup_dir().path,
+            home.join(".config/openclaude-tools/backups")
+        );
+
+        // Claude Desktop uses the platform directory outside Linux
+        let env = Environment {
+            linux: false,
+            ..test_env(home, &[("XDG_CONFIG_HOME", "/xdg")])
+        };
+        let resolver = PathResolver::new(env, PathOverrides::default());
+        assert_eq!(
+            resolver.claude_desktop_config().path,
+            home.join("AppData/Claude/claude_desktop_config.json")
+        );
+    }
+
+    #[test]
+    fn test_environment_variables_are_honored() {
+        let home = Path::new("/home/u");
+        let env = test_env(
+            home,
+            &[
+                ("XDG_CONFIG_HOME", "/xdg"),
+                ("OPENCODE_CONFIG_DIR", "/oc"),
+                ("CLAUDE_CONFIG_DIR", "/cc"),
+            ],
+        );
+        let resolver = PathResolver::new(env, PathOverrides::default());
+
+        let skills = resolver.skills_config();
+        assert_eq!(skills.path, PathBuf::from("/oc/oh-my-opencode.json"));
+        assert_eq!(skills.variable.as_deref(), Some("OPENCODE_CONFIG_DIR"));
+        let claude = resolver.claude_config();
+        assert_eq!(claude.path, PathBuf::from("/cc/.claude.json"));
+        assert_eq!(claude.origin, PathOrigin::Env);
+        let desktop = resolver.claude_desktop_config();
+        assert_eq!(desktop.path, PathBuf::from("/xdg/Claude/claude_desktop_config.json"));
+        assert_eq!(desktop.variable.as_deref(), Some("XDG_CONFIG_HOME"));
+        let backup = resolver.backup_dir();
+        assert_eq!(backup.path, PathBuf::from("/xdg/openclaude-tools/backups"));
+        assert_eq!(backup.variable.as_deref(), Some("XDG_CONFIG_HOME"));
+    }
+
+    #[test]
+    fn test_opencode_config_prefers_explicit_file_then_jsonc() {
+        let dir = tempfile::tempdir().unwrap();
+        let oc_dir = dir.path().join("oc");
+        std::fs::create_dir_all(&oc_dir).unwrap();
+        std::fs::write(oc_dir.join("opencode.jsonc"), "{}").unwrap();
+        let oc_dir = oc_dir.to_string_lossy().to_string();
+
+        let env = test_env(dir.path(), &[("OPENCODE_CONFIG_DIR", &oc_dir)]);
+        let resolver = PathResolver::new(env, PathOverrides::default());
+        assert_eq!(
+            resolver.opencode_config().path,
+            Path::new(&oc_dir).join("opencode.jsonc")
+        );
+
+        let env = test_env(
+            dir.path(),
+            &[("OPENCODE_CONFIG_DIR", &oc_dir), ("OPENCODE_CONFIG", "/work/opencode.json")],
+        );
+        let resolver = PathResolver::new(env, PathOverrides::default());
+        let opencode = resolver.opencode_config();
+        assert_eq!(opencode.path, PathBuf::from("/work/opencode.json"));
+        assert_eq!(opencode.variable.as_deref(), Some("OPENCODE_CONFIG"));
+    }
+
+    #[test]
+    fn test_overrides_win_over_environment() {
+        let env = test_env(Path::new("/home/u"), &[("CLAUDE_CONFIG_DIR", "/cc"), ("OPENCODE_CONFIG", "")]);
+        let mut overrides = PathOverrides::default();
+        overrides.set("claude", Some(PathBuf::from("/custom/claude.json"))).unwrap();
+        let resolver = PathResolver::new(env, overrides);
+
+        let claude = resolver.claude_config();
+        assert_eq!(claude.path, PathBuf::from("/custom/claude.json"));
+        assert_eq!(claude.origin, PathOrigin::Override);
+        assert_eq!(claude.variable, None);
+        // An empty variable counts as unset.
+        assert_eq!(resolver.opencode_config().origin, PathOrigin::Default);
+        // The Claude directory itself is not covered by the file override.
+        assert_eq!(resolver.claude_config_dir().path, PathBuf::from("/cc"));
+    }
+
+    #[test]
+    fn test_override_validation() {
+        let mut overrides = PathOverrides::default();
+
+        assert_eq!(
+            overrides.set("vscode", Some(PathBuf::from("/x"))).unwrap_err().code(),
+            "invalid_input"
+        );
+        assert_eq!(
+            overrides.set("backup", Some(PathBuf::from("relative/dir"))).unwrap_err().code(),
+            "invalid_input"
+        );
+        overrides.set("backup", Some(PathBuf::from("/b"))).unwrap();
+        overrides.set("backup", None).unwrap();
+        assert_eq!(overrides, PathOverrides::default());
+    }
+}
@@ -0,0 +1,184 @@
+//! Read-only discovery of MCP servers contributed by Claude Code plugins.
+//!
+//! Installed plugins live under `~/.claude/plugins/` (marketplace checkouts and
+//! the versioned plugin cache). A plugin that ships MCP servers has a
+//! `.mcp.json` in its root, either as `{ "mcpServers": { ... } }` or as a bare
+//! map of servers. Claude Code owns these files, so they are only ever read.
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories never worth descending into while looking for plugins.
+const SKIPPED_DIRS: [&str; 3] = [".git", "node_modules", ".venv"];
+
+/// How deep below the plugins directory `.mcp.json` files are looked for;
+/// the cache layout is `cache/<marketplace>/<plugin>/<version>/.mcp.json`.
+const MAX_DEPTH: usize = 6;
+
+/// Placeholder Claude Code replaces with the plugin's install directory.
+const PLUGIN_ROOT_VAR: &str = "${CLAUDE_PLUGIN_ROOT}";
+
+/// One MCP server shipped by a plugin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginServer {
+    pub plugin: String,
+    /// The `.mcp.json` the server was found in.
+    pub path: PathBuf,
+    pub name: String,
+    /// Server config with `${CLAUDE_PLUGIN_ROOT}` already expanded, so it can
+    /// be copied to another tool as is.
+    pub config: Value,
+}
+
+/// All plugin MCP servers below `plugins_dir`, in a stable order.
+///
+/// Unreadable or malformed files are logged and skipped; a missing directory
+/// simply yields no servers.
+pub fn discover(plugins_dir: &Path) -> Vec<PluginServer> {
+    let mut files = Vec::new();
+    find_mcp_files(plugins_dir, 0, &mut files);
+    files.sort();
+
+    let mut servers = Vec::new();
+    for path in files {
+        let Some(root) = path.parent() else {
+            continue;
+        };
+        let value = match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<Value>(&content).map_err(|e| e.to_string()))
+        {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("Skipping plugin MCP file {:?}: {}", path, e);
+                continue;
+            }
+        };
+        let entries = match value.get("mcpServers") {
+            Some(Value::Object(map)) => map.clone(),
+            Some(_) => continue,
+            None => match value {
+                Value::Object(map) => map,
+                _ => continue,
+            },
+        };
+
+        let plugin = plugin_name(root);
+        for (name, mut config) in entries {
+            expand_plugin_root(&mut config, &root.to_string_lossy());
+            servers.push(PluginServer {
+                plugin: plugin.clone(),
+                path: path.clone(),
+                name,
+                config,
+            });
+        }
+    }
+    servers
+}
+
+fn find_mcp_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            let skipped = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| SKIPPED_DIRS.contains(&n))
+                .unwrap_or(true);
+            if !skipped && depth < MAX_DEPTH {
+                find_mcp_files(&path, depth + 1, files);
+            }
+        } else if path.file_name().and_then(|n| n.to_str()) == Some(".mcp.json") {
+            files.push(path);
+        }
+    }
+}
+
+/// The plugin's declared name from `.claude-plugin/plugin.json`, falling back
+/// to its directory name (skipping a trailing version directory in the cache).
+fn plugin_name(root: &Path) -> String {
+    let manifest = root.join(".claude-plugin").join("plugin.json");
+    if let Some(name) = fs::read_to_string(manifest)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|value| value.get("name").and_then(Value::as_str).map(String::from))
+    {
+        return name;
+    }
+
+    let dir_name = |path: &Path| path.file_name().map(|n| n.to_string_lossy().to_string());
+    let name = dir_name(root).unwrap_or_default();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        if let Some(parent) = root.parent().and_then(dir_name) {
+            return parent;
+        }
+    }
+    name
+}
+
+fn expand_plugin_root(value: &mut Value, root: &str) {
+    match value {
+        Value::String(s) if s.contains(PLUGIN_ROOT_VAR) => *s = s.replace(PLUGIN_ROOT_VAR, root),
+        Value::Array(items) => items.iter_mut().for_each(|v| expand_plugin_root(v, root)),
+        Value::Object(map) => map.values_mut().for_each(|v| expand_plugin_root(v, root)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_discover_finds_servers_in_both_file_shapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let github = dir.path().join("marketplaces/official/plugins/github");
+        write(&github.join(".claude-plugin/plugin.json"), r#"{ "name": "github-tools" }"#);
+        write(
+            &github.join(".mcp.json"),
+            r#"{ "mcpServers": { "github": { "type": "http", "url": "https://api.example.com/mcp" } } }"#,
+        );
+        let cached = dir.path().join("cache/official/linter/1.2.0");
+        write(
+            &cached.join(".mcp.json"),
+            r#"{ "lint": { "command": "${CLAUDE_PLUGIN_ROOT}/bin/lint", "args": ["--stdio"] } }"#,
+        );
+        write(&dir.path().join("cache/official/linter/1.2.0/node_modules/x/.mcp.json"), "{}");
+        write(&dir.path().join("broken/.mcp.json"), "{ not json");
+
+        let servers = discover(dir.path());
+
+        assert_eq!(servers.len(), 2);
+        let lint = &servers[0];
+        assert_eq!(lint.plugin, "linter");
+        assert_eq!(lint.name, "lint");
+        assert_eq!(
+            lint.config,
+            json!({ "command": format!("{}/bin/lint", cached.to_string_lossy()), "args": ["--stdio"] })
+        );
+        let github_server = &servers[1];
+        assert_eq!(github_server.plugin, "github-tools");
+        assert_eq!(github_server.path, github.join(".mcp.json"));
+    }
+
+    #[test]
+    fn test_discover_without_plugins_dir_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(discover(&dir.path().join("missing")).is_empty());
+    }
+}
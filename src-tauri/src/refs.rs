@@ -0,0 +1,287 @@
+//! Variable references inside MCP config strings.
+//!
+//! OpenCode substitutes `{env:VAR}` and `{file:path}`; Claude Code expands
+//! `${VAR}` and `${VAR:-default}`; Cursor expands `${env:VAR}` and its own
+//! variables such as `${workspaceFolder}`. Strings are parsed into literal
+//! text and references, then written back in the target tool's syntax.
+//! Claude Desktop expands nothing. References the target cannot express are
+//! kept as they are and reported as warnings.
+
+use serde_json::Value;
+
+/// Reference syntax of a tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Opencode,
+    Claude,
+    Cursor,
+    /// No references at all: Claude Desktop passes strings as they are.
+    ClaudeDesktop,
+}
+
+impl Syntax {
+    fn label(self) -> &'static str {
+        match self {
+            Syntax::Opencode => "OpenCode",
+            Syntax::Claude => "Claude Code",
+            Syntax::Cursor => "Cursor",
+            Syntax::ClaudeDesktop => "Claude Desktop",
+        }
+    }
+}
+
+/// Variables Cursor expands itself, written `${name}`.
+const CURSOR_VARIABLES: [&str; 5] = [
+    "userHome",
+    "workspaceFolder",
+    "workspaceFolderBasename",
+    "pathSeparator",
+    "/",
+];
+
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Text(&'a str),
+    Env { name: &'a str, default: Option<&'a str> },
+    File(&'a str),
+    /// One of `CURSOR_VARIABLES`.
+    Cursor(&'a str),
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The reference at the start of `text` and its length, if there is one.
+fn reference(text: &str, syntax: Syntax) -> Option<(Part<'_>, usize)> {
+    let end = text.find('}')?;
+    let part = match syntax {
+        Syntax::Opencode => {
+            let inner = &text[..end];
+            match inner.strip_prefix("{env:") {
+                Some(name) if is_var_name(name) => Part::Env { name, default: None },
+                Some(_) => return None,
+                None => Part::File(inner.strip_prefix("{file:").filter(|path| !path.contains('{'))?),
+            }
+        }
+        Syntax::Claude => {
+            let inner = text[..end].strip_prefix("${")?;
+            match inner.split_once(":-") {
+                Some((name, default)) if is_var_name(name) => Part::Env {
+                    name,
+                    default: Some(default),
+                },
+                None if is_var_name(inner) => Part::Env { name: inner, default: None },
+                _ => return None,
+            }
+        }
+        Syntax::Cursor => {
+            let inner = text[..end].strip_prefix("${")?;
+            match inner.strip_prefix("env:") {
+                Some(name) if is_var_name(name) => Part::Env { name, default: None },
+                Some(_) => return None,
+                None => Part::Cursor(CURSOR_VARIABLES.into_iter().find(|v| *v == inner)?),
+            }
+        }
+        Syntax::ClaudeDesktop => return None,
+    };
+    Some((part, end + 1))
+}
+
+/// The parts of `text`, each with the text it was parsed from.
+fn parse(text: &str, syntax: Syntax) -> Vec<(Part<'_>, &str)> {
+    let opener = match syntax {
+        Syntax::Opencode => "{",
+        Syntax::Claude | Syntax::Cursor => "${",
+        Syntax::ClaudeDesktop => return vec![(Part::Text(text), text)],
+    };
+    let mut parts = Vec::new();
+    let (mut rest, mut literal) = (text, 0);
+    while let Some(start) = rest[literal..].find(opener).map(|i| i + literal) {
+        match reference(&rest[start..], syntax) {
+            Some((part, len)) => {
+                if start > 0 {
+                    parts.push((Part::Text(&rest[..start]), &rest[..start]));
+                }
+                parts.push((part, &rest[start..start + len]));
+                rest = &rest[start + len..];
+                literal = 0;
+            }
+            None => literal = start + opener.len(),
+        }
+    }
+    if !rest.is_empty() {
+        parts.push((Part::Text(rest), rest));
+    }
+    parts
+}
+
+/// Rewrite the references in `text` from `from`'s syntax to `to`'s,
+/// pushing a warning for each one that has no equivalent.
+pub fn translate_str(text: &str, from: Syntax, to: Syntax, warnings: &mut Vec<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (part, source) in parse(text, from) {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            _ if to == Syntax::ClaudeDesktop => {
+                warnings.push(format!(
+                    "Claude Desktop does not expand variables: {} is kept as literal text",
+                    source
+                ));
+                out.push_str(source);
+            }
+            // Only Claude Code has default values
+            Part::Env { name, default: Some(default) } if to == Syntax::Claude => {
+                out.push_str(&format!("${{{}:-{}}}", name, default))
+            }
+            // Written back verbatim: Cursor would read it as its own variable
+            Part::Env { name, default: None } if to == Syntax::Cursor && CURSOR_VARIABLES.contains(&name) => {
+                out.push_str(&format!("${{{}}}", name))
+            }
+            Part::Env { name, default } => {
+                if let Some(default) = default {
+                    warnings.push(format!(
+                        "{} has no default values: the default {:?} of ${{{}}} is dropped",
+                        to.label(),
+                        default,
+                        name
+                    ));
+                }
+                out.push_str(&match to {
+                    Syntax::Opencode => format!("{{env:{}}}", name),
+                    Syntax::Claude => format!("${{{}}}", name),
+                    Syntax::Cursor => format!("${{env:{}}}", name),
+                    Syntax::ClaudeDesktop => unreachable!("handled above"),
+                });
+            }
+            // Only OpenCode has file references
+            Part::File(path) => {
+                if to != Syntax::Opencode {
+                    warnings.push(format!(
+                        "{{file:{}}} has no {} equivalent and is kept as literal text",
+                        path,
+                        to.label()
+                    ));
+                }
+                out.push_str(&format!("{{file:{}}}", path));
+            }
+            // Only Cursor has these
+            Part::Cursor(name) => {
+                if to != Syntax::Cursor {
+                    warnings.push(format!(
+                        "${{{}}} is a Cursor variable with no {} equivalent and is kept as literal text",
+                        name,
+                        to.label()
+                    ));
+                }
+                out.push_str(&format!("${{{}}}", name));
+            }
+        }
+    }
+    out
+}
+
+/// Rewrite the references in every string inside `value`; object keys are
+/// left alone.
+pub fn translate(value: &Value, from: Syntax, to: Syntax, warnings: &mut Vec<String>) -> Value {
+    match value {
+        Value::String(text) => Value::String(translate_str(text, from, to, warnings)),
+        Value::Array(items) => Value::Array(items.iter().map(|v| translate(v, from, to, warnings)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, v)| (key.clone(), translate(v, from, to, warnings)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_translate_both_ways() {
+        let mut warnings = Vec::new();
+        assert_eq!(
+            translate_str("Bearer {env:TOKEN}/{env:X}", Syntax::Opencode, Syntax::Claude, &mut warnings),
+            "Bearer ${TOKEN}/${X}"
+        );
+        assert_eq!(
+            translate_str("${HOME}/data:${PORT:-8080}", Syntax::Claude, Syntax::Opencode, &mut warnings),
+            "{env:HOME}/data:{env:PORT}"
+        );
+        assert_eq!(warnings, [r#"OpenCode has no default values: the default "8080" of ${PORT} is dropped"#]);
+
+        // Text that only looks like a reference stays as it is
+        let mut warnings = Vec::new();
+        for text in ["{json}", "${1}", "$HOME", "{env:A", "${A:-x", "{env:A-B}"] {
+            assert_eq!(translate_str(text, Syntax::Opencode, Syntax::Claude, &mut warnings), text);
+            assert_eq!(translate_str(text, Syntax::Claude, Syntax::Opencode, &mut warnings), text);
+            assert_eq!(translate_str(text, Syntax::Cursor, Syntax::Claude, &mut warnings), text);
+        }
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_cursor_env_references() {
+        let mut warnings = Vec::new();
+        assert_eq!(
+            translate_str("${env:TOKEN}", Syntax::Cursor, Syntax::Claude, &mut warnings),
+            "${TOKEN}"
+        );
+        assert_eq!(
+            translate_str("${TOKEN}:${PORT:-8080}", Syntax::Claude, Syntax::Cursor, &mut warnings),
+            "${env:TOKEN}:${env:PORT}"
+        );
+        // Claude Code keeps its own defaults
+        assert_eq!(
+            translate_str("${PORT:-8080}", Syntax::Claude, Syntax::Claude, &mut warnings),
+            "${PORT:-8080}"
+        );
+        assert_eq!(warnings, [r#"Cursor has no default values: the default "8080" of ${PORT} is dropped"#]);
+    }
+
+    #[test]
+    fn test_cursor_variables_warn_and_survive_round_trip() {
+        let mut warnings = Vec::new();
+        let cursor = "${workspaceFolder}${/}data:${env:HOME}";
+
+        let claude = translate_str(cursor, Syntax::Cursor, Syntax::Claude, &mut warnings);
+        assert_eq!(claude, "${workspaceFolder}${/}data:${HOME}");
+        assert_eq!(translate_str(&claude, Syntax::Claude, Syntax::Cursor, &mut warnings), cursor);
+        assert_eq!(
+            translate_str(cursor, Syntax::Cursor, Syntax::Opencode, &mut warnings),
+            "${workspaceFolder}${/}data:{env:HOME}"
+        );
+        assert_eq!(
+            warnings,
+            [
+                "${workspaceFolder} is a Cursor variable with no Claude Code equivalent and is kept as literal text",
+                "${/} is a Cursor variable with no Claude Code equivalent and is kept as literal text",
+                "${workspaceFolder} is a Cursor variable with no OpenCode equivalent and is kept as literal text",
+                "${/} is a Cursor variable with no OpenCode equivalent and is kept as literal text",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_references_warn_and_nested_values_translate() {
+        let mut warnings = Vec::new();
+        let value = json!({ "Authorization": "{file:~/.token}", "args": ["--key={env:KEY}", 1] });
+
+        assert_eq!(
+            translate(&value, Syntax::Opencode, Syntax::Claude, &mut warnings),
+            json!({ "Authorization": "{file:~/.token}", "args": ["--key=${KEY}", 1] })
+        );
+        assert_eq!(
+            warnings,
+            ["{file:~/.token} has no Claude Code equivalent and is kept as literal text"]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! Sidecar metadata that makes conversion between tools lossless.
+//!
+//! The tools do not have the same fields: OpenCode has `enabled`,
+//! `description` and its own extensions, Claude Code entries can carry keys
+//! OpenCode has no place for. For every converted server the app remembers
+//! its entry in every tool it was converted to or from. Converting back an
+//! entry that has not changed since returns the exact original; converting an
+//! edited entry restores the fields the conversion cannot carry.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::ConfigError;
+use crate::file_io::write_atomic_private;
+use crate::McpScope;
+
+/// Claude Code remote transports. Tools like OpenCode only say `remote`, so
+/// converting an edited remote server back keeps the transport it had last time.
+const CLAUDE_REMOTE_TYPES: [&str; 2] = ["sse", "http"];
+
+/// Where an entry lives: tool id and scope.
+pub type Location<'a> = (&'a str, &'a McpScope);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Sidecar {
+    /// Server name → its entries in every tool and scope it was converted
+    /// between.
+    pub servers: BTreeMap<String, ServerMeta>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// The entries of one server, as last written by a conversion.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ServerMeta {
+    /// The last version handed out. A conversion starting from an entry that
+    /// changed since it was last converted takes the next one.
+    pub version: u64,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Entry {
+    pub tool: String,
+    #[serde(default)]
+    pub scope: McpScope,
+    /// Entries with the same version were converted from one another. An
+    /// entry whose source has moved on to a newer version still provides the
+    /// fields conversion cannot carry, but is never returned as is.
+    pub version: u64,
+    pub config: Value,
+}
+
+impl Entry {
+    fn is_at(&self, (tool, scope): Location) -> bool {
+        self.tool == tool && &self.scope == scope
+    }
+}
+
+impl ServerMeta {
+    fn get(&self, at: Location) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.is_at(at))
+    }
+
+    /// Whether `a_config` at `a` and `b_config` at `b` are both recorded and
+    /// were converted from one another.
+    fn linked(&self, a: Location, a_config: &Value, b: Location, b_config: &Value) -> bool {
+        match (self.get(a), self.get(b)) {
+            (Some(a), Some(b)) => a.config == *a_config && b.config == *b_config && a.version == b.version,
+            _ => false,
+        }
+    }
+
+    fn set(&mut self, at: Location, version: u64, config: Value) {
+        let entry = Entry {
+            tool: at.0.to_string(),
+            scope: at.1.clone(),
+            version,
+            config,
+        };
+        match self.entries.iter_mut().find(|e| e.is_at(at)) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+}
+
+impl Sidecar {
+    /// The entry to write when converting `config` of server `name` from
+    /// `from` to `to`, where `converted` is the plain conversion and
+    /// `target_mapped` the target fields conversion carries. The previous
+    /// target entry when it was converted from (or into) `config`; otherwise
+    /// `converted` with the previous target's unmapped fields put back.
+    pub fn restore(
+        &mut self,
+        name: &str,
+        from: Location,
+        to: Location,
+        config: &Value,
+        mut converted: Value,
+        target_mapped: &[&str],
+    ) -> Value {
+        let server = self.servers.entry(name.to_string()).or_default();
+        let version = match server.get(from) {
+            Some(source) if source.config == *config => {
+                match server.get(to).filter(|target| target.version == source.version) {
+                    Some(target) => return target.config.clone(),
+                    None => source.version,
+                }
+            }
+            // The source changed: what was converted from it is out of date
+            _ => {
+                server.version += 1;
+                server.version
+            }
+        };
+
+        let last_target = server.get(to).map(|e| &e.config);
+        if let (Some(result), Some(Value::Object(target))) = (converted.as_object_mut(), last_target) {
+            for (key, value) in target {
+                if !target_mapped.contains(&key.as_str()) {
+                    result.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        keep_remote_type(&mut converted, last_target);
+
+        server.set(from, version, config.clone());
+        server.set(to, version, converted.clone());
+        converted
+    }
+
+    /// Whether `a_config` at `a` and `b_config` at `b` are the current
+    /// entries of server `name`, i.e. one was converted from the other.
+    pub fn linked(&self, name: &str, a: Location, a_config: &Value, b: Location, b_config: &Value) -> bool {
+        self.servers
+            .get(name)
+            .is_some_and(|server| server.linked(a, a_config, b, b_config))
+    }
+
+    /// Forget the entry of server `name` at `at`, which was removed there,
+    /// along with entries left without one they were converted from or into.
+    /// Returns whether anything changed.
+    pub fn remove(&mut self, name: &str, at: Location) -> bool {
+        let Some(server) = self.servers.get_mut(name) else {
+            return false;
+        };
+        let before = server.entries.len();
+        server.entries.retain(|e| !e.is_at(at));
+        let versions: Vec<u64> = server.entries.iter().map(|e| e.version).collect();
+        server
+            .entries
+            .retain(|e| versions.iter().filter(|&&v| v == e.version).count() > 1);
+        let changed = server.entries.len() != before;
+        if server.entries.is_empty() {
+            self.servers.remove(name);
+        }
+        changed
+    }
+}
+
+/// Put back the last remote `type` of an entry that is still remote.
+fn keep_remote_type(converted: &mut Value, last: Option<&Value>) {
+    let remote = |v: &Value| v.as_str().is_some_and(|t| CLAUDE_REMOTE_TYPES.contains(&t));
+    let Some(last_type) = last.and_then(|last| last.get("type")).filter(|t| remote(t)) else {
+        return;
+    };
+    if let Some(current) = converted.get_mut("type").filter(|t| remote(t)) {
+        *current = last_type.clone();
+    }
+}
+
+/// Load the sidecar from `path`; a missing file means nothing was converted yet.
+pub fn load(path: &Path) -> Result<Sidecar, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| ConfigError::parse(Some(path), &e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Sidecar::default()),
+        Err(e) => Err(ConfigError::read(path, e)),
+    }
+}
+
+pub fn save(path: &Path, sidecar: &Sidecar) -> Result<(), ConfigError> {
+    let content = serde_json::to_string_pretty(sidecar).map_err(ConfigError::internal)?;
+    write_atomic_private(path, content.as_bytes()).map_err(|e| ConfigError::write(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const MAPPED: [&str; 3] = ["type", "command", "url"];
+
+    fn at(tool: &str) -> Location<'_> {
+        (tool, &McpScope::User)
+    }
+
+    #[test]
+    fn test_unchanged_entry_converts_back_exactly() {
+        let mut sidecar = Sidecar::default();
+        let original = json!({ "command": ["a"], "enabled": false, "x-team": "infra" });
+        let claude = sidecar.restore("s", at("opencode"), at("claude"), &original, json!({ "command": "a" }), &MAPPED);
+        assert_eq!(claude, json!({ "command": "a" }));
+
+        let back = sidecar.restore("s", at("claude"), at("opencode"), &claude, json!({ "command": ["a"] }), &MAPPED);
+
+        assert_eq!(back, original);
+    }
+
+    #[test]
+    fn test_edited_entry_keeps_unmapped_fields() {
+        let mut sidecar = Sidecar::default();
+        let original = json!({ "command": ["a"], "enabled": false, "x-team": "infra" });
+        sidecar.restore("s", at("opencode"), at("claude"), &original, json!({ "command": "a" }), &MAPPED);
+
+        let edited = json!({ "command": "b" });
+        let back = sidecar.restore("s", at("claude"), at("opencode"), &edited, json!({ "command": ["b"], "enabled": true }), &MAPPED);
+
+        assert_eq!(back, json!({ "command": ["b"], "enabled": false, "x-team": "infra" }));
+    }
+
+    #[test]
+    fn test_edited_remote_entry_keeps_transport() {
+        let mut sidecar = Sidecar::default();
+        let opencode = json!({ "type": "remote", "url": "https://a" });
+        sidecar.restore("s", at("claude"), at("opencode"), &json!({ "type": "sse", "url": "https://a" }), opencode, &MAPPED);
+
+        let edited = json!({ "type": "remote", "url": "https://b" });
+        let claude = sidecar.restore("s", at("opencode"), at("claude"), &edited, json!({ "type": "http", "url": "https://b" }), &MAPPED);
+        assert_eq!(claude, json!({ "type": "sse", "url": "https://b" }));
+
+        let local = json!({ "type": "local", "command": ["x"] });
+        let claude = sidecar.restore("s", at("opencode"), at("claude"), &local, json!({ "type": "stdio" }), &MAPPED);
+        assert_eq!(claude["type"], "stdio");
+    }
+
+    #[test]
+    fn test_sidecar_survives_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("conversion-meta.json");
+        let mut sidecar = Sidecar::default();
+        let original = json!({ "command": "a", "oauth": { "clientId": "x" } });
+        let opencode = sidecar.restore("s", at("claude"), at("opencode"), &original, json!({ "command": ["a"] }), &MAPPED);
+        save(&path, &sidecar).unwrap();
+
+        let mut loaded = load(&path).unwrap();
+
+        let back = loaded.restore("s", at("opencode"), at("claude"), &opencode, json!({ "command": "a" }), &MAPPED);
+        assert_eq!(back, original);
+        assert!(load(&dir.path().join("missing.json")).unwrap().servers.is_empty());
+    }
+
+    #[test]
+    fn test_three_tools_keep_each_others_entries() {
+        let mut sidecar = Sidecar::default();
+        let opencode = json!({ "command": ["a"], "enabled": false, "description": "d" });
+        let claude = sidecar.restore("s", at("opencode"), at("claude"), &opencode, json!({ "command": "a" }), &MAPPED);
+        let cursor = sidecar.restore("s", at("claude"), at("cursor"), &claude, json!({ "command": "a" }), &MAPPED);
+        assert_eq!(cursor, json!({ "command": "a" }));
+
+        // Syncing on to a third tool does not forget the OpenCode entry
+        let back = sidecar.restore("s", at("claude"), at("opencode"), &claude, json!({ "command": ["a"], "enabled": true }), &MAPPED);
+        assert_eq!(back, opencode);
+        assert!(sidecar.linked("s", at("opencode"), &opencode, at("claude"), &claude));
+
+        // An edit in Cursor makes the other entries stale, but their
+        // unmapped fields still come back
+        let edited = json!({ "command": "b" });
+        sidecar.restore("s", at("cursor"), at("claude"), &edited, json!({ "command": "b" }), &MAPPED);
+        assert!(!sidecar.linked("s", at("opencode"), &opencode, at("claude"), &claude));
+        let back = sidecar.restore("s", at("claude"), at("opencode"), &json!({ "command": "b" }), json!({ "command": ["b"], "enabled": true }), &MAPPED);
+        assert_eq!(back, json!({ "command": ["b"], "enabled": false, "description": "d" }));
+    }
+
+    #[test]
+    fn test_scopes_are_separate_and_removal_forgets() {
+        let mut sidecar = Sidecar::default();
+        let project = McpScope::Project { path: "/repo".to_string() };
+        let opencode = json!({ "command": ["a"], "enabled": false });
+        sidecar.restore("gh", at("opencode"), at("claude"), &opencode, json!({ "command": "a" }), &MAPPED);
+        let in_project = json!({ "command": "p", "x-project": true });
+        sidecar.restore("gh", ("claude", &project), at("cursor"), &in_project, json!({ "command": "p" }), &MAPPED);
+
+        // The project entry did not overwrite the user scope one
+        let back = sidecar.restore("gh", at("claude"), at("opencode"), &json!({ "command": "a" }), json!({}), &MAPPED);
+        assert_eq!(back, opencode);
+
+        // Once a server is deleted on one side, a new one of the same name
+        // does not inherit its fields
+        assert!(sidecar.remove("gh", at("claude")));
+        assert!(sidecar.remove("gh", ("claude", &project)));
+        assert!(sidecar.servers.is_empty());
+        let fresh = sidecar.restore("gh", at("claude"), at("opencode"), &json!({ "command": "b" }), json!({ "command": ["b"], "enabled": true }), &MAPPED);
+        assert_eq!(fresh, json!({ "command": ["b"], "enabled": true }));
+        assert!(!sidecar.remove("other", at("claude")));
+    }
+}
@@ -0,0 +1,307 @@
+//! Automatic pre-write snapshots.
+//!
+//! Every command that modifies a config file first copies the current content
+//! into the backup directory as `auto_<source>_<timestamp>.json`. Snapshots are
+//! self-describing (they record the file they came from and its exact text,
+//! comments included), so they can be restored without guessing the target.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::error::ConfigError;
+use crate::file_io::write_atomic_private;
+
+const SNAPSHOT_PREFIX: &str = "auto_";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S_%3f";
+
+/// How many automatic snapshots are kept, per source file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotRetention {
+    /// Keep at most this many snapshots per source; `None` keeps all.
+    pub max_count: Option<usize>,
+    /// Drop snapshots older than this many days; `None` keeps them forever.
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for SnapshotRetention {
+    fn default() -> Self {
+        Self {
+            max_count: Some(20),
+            max_age_days: Some(30),
+        }
+    }
+}
+
+/// On-disk content of an automatic snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoSnapshot {
+    pub timestamp: String,
+    pub source: String,
+    /// The file the snapshot was taken from.
+    pub path: String,
+    /// Exact text of the file before it was modified.
+    pub content: String,
+}
+
+/// A manual backup or automatic snapshot, as shown in the backup list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub kind: String, // "manual" or "auto"
+    pub source: Option<String>,
+    pub filename: String,
+    pub path: String,
+    pub target_path: Option<String>,
+    pub created_at: String, // Human readable date
+}
+
+pub fn is_snapshot_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with(SNAPSHOT_PREFIX) && n.ends_with(".json"))
+        .unwrap_or(false)
+}
+
+/// Snapshot `content` of `target` into `dir`, then prune old snapshots of the same source.
+pub fn take_snapshot(
+    dir: &Path,
+    source: &str,
+    target: &Path,
+    content: &str,
+    retention: &SnapshotRetention,
+) -> io::Result<PathBuf> {
+    let snapshot_path = write_snapshot(dir, source, target, content)?;
+    if let Err(e) = prune(dir, source, retention, SystemTime::now()) {
+        log::warn!("Failed to prune snapshots for {}: {}", source, e);
+    }
+    Ok(snapshot_path)
+}
+
+/// Snapshot `content` of `target` into `dir` without pruning, for writes that
+/// may still be abandoned; the caller prunes once the write went through.
+pub fn write_snapshot(dir: &Path, source: &str, target: &Path, content: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let now = chrono::Local::now();
+    let snapshot = AutoSnapshot {
+        timestamp: now.to_rfc3339(),
+        source: source.to_string(),
+        path: target.to_string_lossy().to_string(),
+        content: content.to_string(),
+    };
+    let data = serde_json::to_string_pretty(&snapshot).map_err(io::Error::other)?;
+
+    let stem = format!("{}{}_{}", SNAPSHOT_PREFIX, source, now.format(TIMESTAMP_FORMAT));
+    let mut snapshot_path = dir.join(format!("{}.json", stem));
+    let mut n = 1;
+    while snapshot_path.exists() {
+        snapshot_path = dir.join(format!("{}_{}.json", stem, n));
+        n += 1;
+    }
+
+    // Snapshots copy tokens and secrets out of the config files
+    write_atomic_private(&snapshot_path, data.as_bytes())?;
+    log::info!("Snapshot of {:?} saved to {:?}", target, snapshot_path);
+    Ok(snapshot_path)
+}
+
+/// Snapshot files of `source`, newest first.
+fn snapshots_for(dir: &Path, source: &str) -> io::Result<Vec<PathBuf>> {
+    let prefix = format!("{}{}_", SNAPSHOT_PREFIX, source);
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with(&prefix) && n.ends_with(".json"))
+                .unwrap_or(false)
+        })
+        .collect();
+    paths.sort_by(|a, b| b.cmp(a));
+    Ok(paths)
+}
+
+/// Delete snapshots of `source` that exceed the retention policy.
+pub fn prune(
+    dir: &Path,
+    source: &str,
+    retention: &SnapshotRetention,
+    now: SystemTime,
+) -> io::Result<usize> {
+    let max_age = retention
+        .max_age_days
+        .map(|days| Duration::from_secs(days * 24 * 60 * 60));
+    let mut removed = 0;
+
+    for (index, path) in snapshots_for(dir, source)?.into_iter().enumerate() {
+        let too_many = retention.max_count.map(|max| index >= max).unwrap_or(false);
+        let too_old = match max_age {
+            Some(max_age) => fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .map(|age| age > max_age)
+                .unwrap_or(false),
+            None => false,
+        };
+
+        if too_many || too_old {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+
+    if removed > 0 {
+        log::info!("Pruned {} old {} snapshots", removed, source);
+    }
+    Ok(removed)
+}
+
+pub fn read_snapshot(path: &Path) -> Result<AutoSnapshot, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|e| ConfigError::read(path, e))?;
+    serde_json::from_str(&content).map_err(|e| ConfigError::parse(Some(path), &e))
+}
+
+/// All manual backups and automatic snapshots in `dir`, newest first.
+pub fn list(dir: &Path) -> io::Result<Vec<SnapshotInfo>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut items: Vec<(SystemTime, SnapshotInfo)> = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let Some(filename) = path.file_name().and_then(|n| n.to_str()).map(String::from) else {
+            continue;
+        };
+        let modified = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let created_at = chrono::DateTime::<chrono::Local>::from(modified)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+
+        let info = if is_snapshot_file(&path) {
+            let (source, target_path) = match read_snapshot(&path) {
+                Ok(snapshot) => (Some(snapshot.source), Some(snapshot.path)),
+                Err(_) => (None, None),
+            };
+            SnapshotInfo {
+                kind: "auto".to_string(),
+                source,
+                filename,
+                path: path.to_string_lossy().to_string(),
+                target_path,
+                created_at,
+            }
+        } else if filename.starts_with("backup_") && filename.ends_with(".json") {
+            SnapshotInfo {
+                kind: "manual".to_string(),
+                source: None,
+                filename,
+                path: path.to_string_lossy().to_string(),
+                target_path: None,
+                created_at,
+            }
+        } else {
+            continue;
+        };
+        items.push((modified, info));
+    }
+
+    items.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.filename.cmp(&a.1.filename)));
+    Ok(items.into_iter().map(|(_, info)| info).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_snapshot_records_source_and_exact_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("opencode.jsonc");
+        let content = "{\n  // keep this comment\n  \"mcp\": {}\n}\n";
+
+        let path = take_snapshot(dir.path(), "opencode", &target, content, &SnapshotRetention::default())
+            .unwrap();
+
+        assert!(is_snapshot_file(&path));
+        let snapshot = read_snapshot(&path).unwrap();
+        assert_eq!(snapshot.source, "opencode");
+        assert_eq!(snapshot.path, target.to_string_lossy());
+        assert_eq!(snapshot.content, content);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_per_source() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("auto_claude_20260101_00000{}_000.json", i)), "{}").unwrap();
+        }
+        fs::write(dir.path().join("auto_opencode_20260101_000000_000.json"), "{}").unwrap();
+        fs::write(dir.path().join("backup_20260101_000000.json"), "{}").unwrap();
+        let retention = SnapshotRetention {
+            max_count: Some(2),
+            max_age_days: None,
+        };
+
+        let removed = prune(dir.path(), "claude", &retention, SystemTime::now()).unwrap();
+
+        assert_eq!(removed, 3);
+        let left = snapshots_for(dir.path(), "claude").unwrap();
+        let names: Vec<_> = left
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            ["auto_claude_20260101_000004_000.json", "auto_claude_20260101_000003_000.json"]
+        );
+        assert!(dir.path().join("auto_opencode_20260101_000000_000.json").exists());
+        assert!(dir.path().join("backup_20260101_000000.json").exists());
+    }
+
+    #[test]
+    fn test_prune_drops_snapshots_older_than_max_age() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("auto_skills_20260101_000000_000.json"), "{}").unwrap();
+        let retention = SnapshotRetention {
+            max_count: None,
+            max_age_days: Some(7),
+        };
+
+        let now = SystemTime::now();
+        assert_eq!(prune(dir.path(), "skills", &retention, now).unwrap(), 0);
+
+        let later = now + Duration::from_secs(8 * 24 * 60 * 60);
+        assert_eq!(prune(dir.path(), "skills", &retention, later).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_list_includes_manual_and_auto_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join(".claude.json");
+        take_snapshot(dir.path(), "claude", &target, "{}", &SnapshotRetention::default()).unwrap();
+        fs::write(dir.path().join("backup_20260101_000000.json"), "{}").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let items = list(dir.path()).unwrap();
+
+        assert_eq!(items.len(), 2);
+        let auto = items.iter().find(|i| i.kind == "auto").unwrap();
+        assert_eq!(auto.source.as_deref(), Some("claude"));
+        assert_eq!(auto.target_path.as_deref(), Some(target.to_string_lossy().as_ref()));
+        assert!(items.iter().any(|i| i.kind == "manual"));
+    }
+}
@@ -0,0 +1,274 @@
+//! Per-tool validation of MCP entries before they are written.
+//!
+//! Each tool only starts when its MCP entries have the shape it expects, so a
+//! bad entry is reported with the path of the offending field, e.g.
+//! `mcp.github.command: expected array of strings`. Unknown fields are left
+//! alone; only the fields the tool actually reads are checked.
+
+use serde_json::{Map, Value};
+
+/// Collects problems for one entry, prefixed with its location in the file.
+struct Checker<'a> {
+    prefix: String,
+    entry: &'a Map<String, Value>,
+    errors: Vec<String>,
+}
+
+impl<'a> Checker<'a> {
+    fn error(&mut self, field: &str, message: &str) {
+        self.errors.push(format!("{}.{}: {}", self.prefix, field, message));
+    }
+
+    fn required(&mut self, field: &str, transport: &str) -> Option<&'a Value> {
+        let value = self.entry.get(field);
+        if value.is_none() {
+            self.error(field, &format!("required for {} servers", transport));
+        }
+        value
+    }
+
+    fn string(&mut self, field: &str, value: Option<&Value>) {
+        match value {
+            Some(Value::String(s)) if !s.trim().is_empty() => {}
+            Some(Value::String(_)) => self.error(field, "must not be empty"),
+            Some(_) => self.error(field, "expected string"),
+            None => {}
+        }
+    }
+
+    fn string_array(&mut self, field: &str, value: Option<&Value>, non_empty: bool) {
+        match value {
+            Some(Value::Array(items)) if non_empty && items.is_empty() => {
+                self.error(field, "expected non-empty array of strings")
+            }
+            Some(Value::Array(items)) if items.iter().all(Value::is_string) => {}
+            Some(_) => self.error(field, "expected array of strings"),
+            None => {}
+        }
+    }
+
+    fn string_map(&mut self, field: &str) {
+        match self.entry.get(field) {
+            Some(Value::Object(map)) => {
+                for (key, value) in map {
+                    if !value.is_string() {
+                        self.error(&format!("{}.{}", field, key), "expected string");
+                    }
+                }
+            }
+            Some(_) => self.error(field, "expected object of strings"),
+            None => {}
+        }
+    }
+
+    fn boolean(&mut self, field: &str) {
+        if matches!(self.entry.get(field), Some(value) if !value.is_boolean()) {
+            self.error(field, "expected boolean");
+        }
+    }
+
+    fn timeout(&mut self, field: &str) {
+        if matches!(self.entry.get(field), Some(value) if value.as_u64().filter(|t| *t > 0).is_none()) {
+            self.error(field, "expected positive integer (milliseconds)");
+        }
+    }
+}
+
+/// Run `check` on `config` as the entry `prefix`, or report that it is not an object.
+fn check_entry(prefix: String, config: &Value, check: impl FnOnce(&mut Checker)) -> Vec<String> {
+    let Some(entry) = config.as_object() else {
+        return vec![format!("{}: expected object", prefix)];
+    };
+    let mut checker = Checker {
+        prefix,
+        entry,
+        errors: Vec::new(),
+    };
+    check(&mut checker);
+    checker.errors
+}
+
+/// Validate an OpenCode `mcp.<name>` entry.
+///
+/// - `local`: `command` is a non-empty array of strings, `environment` maps to strings
+/// - `remote`: `url` is a string, `headers` maps to strings
+pub fn opencode_mcp(name: &str, config: &Value) -> Vec<String> {
+    check_entry(format!("mcp.{}", name), config, |c| {
+        match c.entry.get("type").and_then(Value::as_str) {
+            Some("local") => {
+                let command = c.required("command", "local");
+                c.string_array("command", command, true);
+                c.string_map("environment");
+            }
+            Some("remote") => {
+                let url = c.required("url", "remote");
+                c.string("url", url);
+                c.string_map("headers");
+            }
+            _ => c.error("type", r#"expected "local" or "remote""#),
+        }
+        c.boolean("enabled");
+        c.timeout("timeout");
+    })
+}
+
+/// Validate a Claude Code `mcpServers.<name>` entry.
+///
+/// - `stdio` (the default when `type` is missing): `command` is a string,
+///   `args` an array of strings, `env` maps to strings
+/// - `sse` / `http`: `url` is a string, `headers` maps to strings
+pub fn claude_mcp(name: &str, config: &Value) -> Vec<String> {
+    check_entry(format!("mcpServers.{}", name), config, |c| {
+        match c.entry.get("type") {
+            None => claude_stdio(c),
+            Some(Value::String(t)) if t == "stdio" => claude_stdio(c),
+            Some(Value::String(t)) if t == "sse" || t == "http" => {
+                let url = c.required("url", t);
+                c.string("url", url);
+                c.string_map("headers");
+            }
+            _ => c.error("type", r#"expected "stdio", "sse" or "http""#),
+        }
+    })
+}
+
+/// Validate a Claude Desktop `mcpServers.<name>` entry. Desktop only runs
+/// stdio servers, so remote entries are rejected.
+pub fn claude_desktop_mcp(name: &str, config: &Value) -> Vec<String> {
+    check_entry(format!("mcpServers.{}", name), config, |c| {
+        match c.entry.get("type") {
+            None => {}
+            Some(Value::String(t)) if t == "stdio" => {}
+            _ => c.error("type", "Claude Desktop only supports stdio servers"),
+        }
+        if c.entry.contains_key("url") {
+            c.error("url", "remote servers are not supported by Claude Desktop");
+        }
+        claude_stdio(c);
+    })
+}
+
+/// Validate a Cursor `mcpServers.<name>` entry. `type` is optional: entries
+/// with a `url` are remote, the others stdio.
+///
+/// - stdio: as for Claude Code, plus an optional `envFile` path
+/// - remote: `url` is a string, `headers` maps to strings
+pub fn cursor_mcp(name: &str, config: &Value) -> Vec<String> {
+    check_entry(format!("mcpServers.{}", name), config, |c| {
+        match (c.entry.get("type"), c.entry.contains_key("url")) {
+            (None, false) => claude_stdio(c),
+            (Some(Value::String(t)), _) if t == "stdio" => claude_stdio(c),
+            (None, true) => cursor_remote(c, "remote"),
+            (Some(Value::String(t)), _) if ["sse", "http", "streamable-http"].contains(&t.as_str()) => {
+                cursor_remote(c, t)
+            }
+            _ => c.error("type", r#"expected "stdio", "sse", "http" or "streamable-http""#),
+        }
+        let env_file = c.entry.get("envFile");
+        c.string("envFile", env_file);
+    })
+}
+
+fn cursor_remote(c: &mut Checker, transport: &str) {
+    let url = c.required("url", transport);
+    c.string("url", url);
+    c.string_map("headers");
+}
+
+fn claude_stdio(c: &mut Checker) {
+    let command = c.required("command", "stdio");
+    c.string("command", command);
+    let args = c.entry.get("args");
+    c.string_array("args", args, false);
+    c.string_map("env");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_valid_entries_pass() {
+        assert!(opencode_mcp("fs", &json!({"type": "local", "command": ["npx", "fs"], "enabled": true})).is_empty());
+        assert!(opencode_mcp("api", &json!({"type": "remote", "url": "https://x", "description": "d"})).is_empty());
+        assert!(claude_mcp("fs", &json!({"command": "npx", "args": ["fs"], "env": {"A": "1"}})).is_empty());
+        assert!(claude_mcp("api", &json!({"type": "http", "url": "https://x"})).is_empty());
+    }
+
+    #[test]
+    fn test_opencode_errors_name_the_field() {
+        let errors = opencode_mcp(
+            "github",
+            &json!({"type": "local", "command": "npx github", "environment": {"TOKEN": 1}, "enabled": "yes"}),
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                "mcp.github.command: expected array of strings",
+                "mcp.github.environment.TOKEN: expected string",
+                "mcp.github.enabled: expected boolean",
+            ]
+        );
+        assert_eq!(
+            opencode_mcp("x", &json!({"command": ["a"]})),
+            vec![r#"mcp.x.type: expected "local" or "remote""#]
+        );
+        assert_eq!(
+            opencode_mcp("x", &json!({"type": "local", "command": []})),
+            vec!["mcp.x.command: expected non-empty array of strings"]
+        );
+    }
+
+    #[test]
+    fn test_claude_errors_per_transport() {
+        assert_eq!(
+            claude_mcp("fs", &json!({"type": "stdio"})),
+            vec!["mcpServers.fs.command: required for stdio servers"]
+        );
+        assert_eq!(
+            claude_mcp("api", &json!({"type": "sse", "headers": []})),
+            vec![
+                "mcpServers.api.url: required for sse servers",
+                "mcpServers.api.headers: expected object of strings",
+            ]
+        );
+        assert_eq!(
+            claude_mcp("x", &json!({"type": "ws", "url": "ws://x"})),
+            vec![r#"mcpServers.x.type: expected "stdio", "sse" or "http""#]
+        );
+        assert_eq!(claude_mcp("x", &json!([])), vec!["mcpServers.x: expected object"]);
+    }
+
+    #[test]
+    fn test_cursor_infers_remote_from_url() {
+        assert!(cursor_mcp("api", &json!({"url": "https://x", "headers": {"A": "1"}})).is_empty());
+        assert!(cursor_mcp("fs", &json!({"command": "npx", "env": {"A": "1"}, "envFile": ".env"})).is_empty());
+        assert_eq!(
+            cursor_mcp("api", &json!({"url": 1})),
+            vec!["mcpServers.api.url: expected string"]
+        );
+        assert_eq!(
+            cursor_mcp("x", &json!({"type": "sse"})),
+            vec!["mcpServers.x.url: required for sse servers"]
+        );
+        assert_eq!(
+            cursor_mcp("x", &json!({"args": ["a"]})),
+            vec!["mcpServers.x.command: required for stdio servers"]
+        );
+    }
+
+    #[test]
+    fn test_claude_desktop_rejects_remote_servers() {
+        assert!(claude_desktop_mcp("fs", &json!({"command": "npx", "args": ["fs"]})).is_empty());
+        assert_eq!(
+            claude_desktop_mcp("api", &json!({"type": "http", "url": "https://x"})),
+            vec![
+                "mcpServers.api.type: Claude Desktop only supports stdio servers",
+                "mcpServers.api.url: remote servers are not supported by Claude Desktop",
+                "mcpServers.api.command: required for stdio servers",
+            ]
+        );
+    }
+}
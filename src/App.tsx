@@ -7,11 +7,12 @@ import { SkillsMgmtPage } from './pages/SkillsMgmtPage';
 import { ModelsMgmtPage } from './pages/ModelsMgmtPage';
 import { BackupListPage } from './pages/BackupListPage';
 import { useSettingsStore } from './stores/settingsStore';
+import type { McpSource } from './types';
 import './i18n';
 
 function App() {
   const [activePage, setActivePage] = useState('mcp');
-  const [activeTab, setActiveTab] = useState<'opencode' | 'claude'>('opencode');
+  const [activeTab, setActiveTab] = useState<McpSource>('opencode');
   const { initializeTheme, theme } = useSettingsStore();
 
   useEffect(() => {
@@ -1,10 +1,12 @@
 import { useTranslation } from 'react-i18next';
 import { useSettingsStore } from '../stores/settingsStore';
 import { Sun, Moon, Globe, Settings } from 'lucide-react';
+import { MCP_SOURCE_LABELS } from '../types';
+import type { McpSource } from '../types';
 
 interface HeaderProps {
-  activeTab: 'opencode' | 'claude';
-  onTabChange: (tab: 'opencode' | 'claude') => void;
+  activeTab: McpSource;
+  onTabChange: (tab: McpSource) => void;
 }
 
 export function Header({ activeTab, onTabChange }: HeaderProps) {
@@ -24,38 +26,25 @@ export function Header({ activeTab, onTabChange }: HeaderProps) {
       <div 
         className={`flex p-1 rounded-xl ${theme === 'light' ? 'bg-slate-100' : 'bg-slate-800'}`}
       >
-        <button
-          onClick={() => onTabChange('opencode')}
-          className={`
-            px-6 py-1.5 text-sm font-medium rounded-lg transition-all
-            ${activeTab === 'opencode'
-              ? theme === 'light'
-                ? 'bg-white shadow-sm text-indigo-600'
-                : 'bg-slate-700 shadow-sm text-indigo-400'
-              : theme === 'light'
-                ? 'text-slate-500 hover:text-slate-700'
-                : 'text-slate-400 hover:text-slate-200'
-            }
-          `}
-        >
-          OpenCode
-        </button>
-        <button
-          onClick={() => onTabChange('claude')}
-          className={`
-            px-6 py-1.5 text-sm font-medium rounded-lg transition-all
-            ${activeTab === 'claude'
-              ? theme === 'light'
-                ? 'bg-white shadow-sm text-indigo-600'
-                : 'bg-slate-700 shadow-sm text-indigo-400'
-              : theme === 'light'
-                ? 'text-slate-500 hover:text-slate-700'
-                : 'text-slate-400 hover:text-slate-200'
-            }
-          `}
-        >
-          Claude code
-        </button>
+        {(Object.keys(MCP_SOURCE_LABELS) as McpSource[]).map((tab) => (
+          <button
+            key={tab}
+            onClick={() => onTabChange(tab)}
+            className={`
+              px-6 py-1.5 text-sm font-medium rounded-lg transition-all
+              ${activeTab === tab
+                ? theme === 'light'
+                  ? 'bg-white shadow-sm text-indigo-600'
+                  : 'bg-slate-700 shadow-sm text-indigo-400'
+                : theme === 'light'
+                  ? 'text-slate-500 hover:text-slate-700'
+                  : 'text-slate-400 hover:text-slate-200'
+              }
+            `}
+          >
+            {MCP_SOURCE_LABELS[tab]}
+          </button>
+        ))}
       </div>
 
       {/* Right Side Icons */}
@@ -24,6 +24,8 @@ const resources = {
       'mcp.noMcp': 'No MCP configurations found',
       'mcp.noOpenCodeMcp': 'No OpenCode MCP configurations. Click the button above to add one.',
       'mcp.noClaudeMcp': 'No Claude Code MCP configurations found. MCPs will be loaded from Claude Code plugins.',
+      'mcp.noClaudeDesktopMcp': 'No Claude Desktop MCP configurations found in claude_desktop_config.json.',
+      'mcp.noCursorMcp': 'No Cursor MCP configurations found in ~/.cursor/mcp.json or the .cursor/mcp.json of registered projects.',
       'mcp.addTitle': 'Add New MCP',
       'mcp.editTitle': 'Edit MCP',
       'mcp.name': 'Name',
@@ -34,9 +36,27 @@ const resources = {
       'mcp.urlPlaceholder': 'https://example.com/mcp',
       'mcp.command': 'Command',
       'mcp.commandPlaceholder': 'node, mcp-server.js',
-      'mcp.syncToClaude': 'Sync to Claude code',
-      'mcp.syncToOpenCode': 'Sync to OpenCode',
+      'mcp.syncTo': 'Sync to {{tool}}',
+      'mcp.cannotDisable': '{{tool}} cannot disable servers; delete it instead',
       'mcp.viewLog': 'View Log',
+      'mcp.validationFailed': 'This configuration is invalid for the target tool:',
+      'mcp.saveAnyway': 'Save anyway?',
+      'mcp.scope': 'Scope',
+      'mcp.scopeUser': 'User (all projects)',
+      'mcp.scopeLocal': 'Local (~/.claude.json)',
+      'mcp.scopeProject': 'Project (.mcp.json)',
+      'mcp.scopePlugin': 'Plugin',
+      'mcp.readOnly': 'read-only',
+      'mcp.enable': 'Enable',
+      'mcp.disable': 'Disable',
+      'mcp.sameInOther': 'same in {{tool}}',
+      'mcp.differsInOther': 'differs from {{tool}}',
+      'mcp.syncReport': 'Synced. Conversion changes:',
+      'mcp.projectPathPlaceholder': '/absolute/path/to/project',
+      'mcp.projects': 'Projects',
+      'mcp.addProject': 'Add Project',
+      'mcp.removeProject': 'Stop managing this project',
+      'mcp.selectProject': 'Select project folder',
 
       // Skills Management
       'skills.title': 'Skills Management',
@@ -108,6 +128,43 @@ const resources = {
       'error.parseFailed': 'Failed to parse configuration',
       'error.saveFailed': 'Failed to save configuration',
       'error.syncFailed': 'Synchronization failed',
+      'sync.added': 'To add',
+      'sync.updated': 'To update',
+      'sync.removed': 'Only in target',
+      'sync.inSync': 'Nothing to sync.',
+      'sync.partial': 'Sync finished with conflicts or invalid entries',
+      'sync.tools': 'Tools',
+      'sync.strategyLabel': 'Strategy',
+      'sync.strategy.only-missing': 'Only missing',
+      'sync.strategy.overwrite': 'Overwrite',
+      'sync.strategy.skip-on-conflict': 'Skip on conflict',
+      'sync.strategy.prefer-newer': 'Prefer newer',
+      'sync.preferNewerHint': 'Decided per server: the side that changed since the last sync wins; if both did, the one this app wrote last. Servers where that cannot be told are reported as conflicts.',
+      'sync.dryRun': 'Dry run',
+      'sync.dryRunNote': 'Dry run: nothing was written.',
+      'sync.action.added': 'added',
+      'sync.action.updated': 'updated',
+      'sync.action.unchanged': 'unchanged',
+      'sync.action.skipped': 'skipped',
+      'sync.action.conflict': 'conflict',
+      'sync.action.invalid': 'invalid',
+      'sync.bidirectional': 'Two-way sync: apply changes made on either side since the last sync',
+      'sync.outcome.copied_to_right': 'copied to {{right}}',
+      'sync.outcome.copied_to_left': 'copied to {{left}}',
+      'sync.outcome.deleted_from_right': 'deleted from {{right}}',
+      'sync.outcome.deleted_from_left': 'deleted from {{left}}',
+      'sync.outcome.conflict': 'conflict',
+      'sync.outcome.invalid': 'invalid',
+      'sync.deleted': '(deleted)',
+      'sync.report.dropped': 'Dropped',
+      'sync.report.renamed': 'Renamed',
+      'sync.report.inferred': 'Inferred',
+      'sync.report.restored': 'Restored from last conversion',
+      'sync.keep': 'Keep {{tool}} version',
+      'sync.history': 'History',
+      'sync.historyEmpty': 'No changes recorded yet.',
+      'sync.undo': 'Undo',
+      'sync.undoAnyway': 'Undo anyway and overwrite the newer change?',
       
       // Backup
       'backup.title': 'MCP Backup',
@@ -145,6 +202,8 @@ const resources = {
       'mcp.noMcp': '未找到 MCP 配置',
       'mcp.noOpenCodeMcp': '未找到 OpenCode MCP 配置。点击上方按钮添加。',
       'mcp.noClaudeMcp': '未找到 Claude Code MCP 配置。MCP 将从 Claude Code 插件加载。',
+      'mcp.noClaudeDesktopMcp': '未在 claude_desktop_config.json 中找到 Claude Desktop MCP 配置。',
+      'mcp.noCursorMcp': '未在 ~/.cursor/mcp.json 或已登记项目的 .cursor/mcp.json 中找到 Cursor MCP 配置。',
       'mcp.addTitle': '添加新 MCP',
       'mcp.editTitle': '编辑 MCP',
       'mcp.name': '名称',
@@ -155,9 +214,27 @@ const resources = {
       'mcp.urlPlaceholder': 'https://example.com/mcp',
       'mcp.command': '命令',
       'mcp.commandPlaceholder': 'node, mcp-server.js',
-      'mcp.syncToClaude': '同步到 Claude code',
-      'mcp.syncToOpenCode': '同步到 OpenCode',
+      'mcp.syncTo': '同步到 {{tool}}',
+      'mcp.cannotDisable': '{{tool}} 无法停用服务器，请直接删除',
       'mcp.viewLog': '查看日志',
+      'mcp.validationFailed': '该配置不符合目标工具的格式要求：',
+      'mcp.saveAnyway': '仍要保存吗？',
+      'mcp.scope': '作用范围',
+      'mcp.scopeUser': '用户（所有项目）',
+      'mcp.scopeLocal': '本地（~/.claude.json）',
+      'mcp.scopeProject': '项目（.mcp.json）',
+      'mcp.scopePlugin': '插件',
+      'mcp.readOnly': '只读',
+      'mcp.enable': '启用',
+      'mcp.disable': '停用',
+      'mcp.sameInOther': '与 {{tool}} 一致',
+      'mcp.differsInOther': '与 {{tool}} 不同',
+      'mcp.syncReport': '同步完成，格式转换变更如下：',
+      'mcp.projectPathPlaceholder': '/项目/绝对路径',
+      'mcp.projects': '项目',
+      'mcp.addProject': '添加项目',
+      'mcp.removeProject': '不再管理该项目',
+      'mcp.selectProject': '选择项目文件夹',
 
       // Skills Management
       'skills.title': 'Skills 管理',
@@ -230,6 +307,43 @@ const resources = {
       'error.parseFailed': '解析配置文件失败',
       'error.saveFailed': '保存配置文件失败',
       'error.syncFailed': '同步失败',
+      'sync.added': '将新增',
+      'sync.updated': '将更新',
+      'sync.removed': '仅存在于目标',
+      'sync.inSync': '无需同步。',
+      'sync.partial': '同步完成，但存在冲突或无效条目',
+      'sync.tools': '工具',
+      'sync.strategyLabel': '策略',
+      'sync.strategy.only-missing': '仅同步缺失项',
+      'sync.strategy.overwrite': '覆盖',
+      'sync.strategy.skip-on-conflict': '冲突时跳过',
+      'sync.strategy.prefer-newer': '以较新版本为准',
+      'sync.preferNewerHint': '逐个服务器判断：上次同步后只有一侧修改过的，以该侧为准；两侧都改过的，以本应用最后写入的一侧为准。无法判断的服务器作为冲突列出。',
+      'sync.dryRun': '试运行',
+      'sync.dryRunNote': '试运行：未写入任何内容。',
+      'sync.action.added': '已新增',
+      'sync.action.updated': '已更新',
+      'sync.action.unchanged': '无变化',
+      'sync.action.skipped': '已跳过',
+      'sync.action.conflict': '冲突',
+      'sync.action.invalid': '无效',
+      'sync.bidirectional': '双向同步：应用自上次同步以来任一侧的修改',
+      'sync.outcome.copied_to_right': '已复制到 {{right}}',
+      'sync.outcome.copied_to_left': '已复制到 {{left}}',
+      'sync.outcome.deleted_from_right': '已从 {{right}} 删除',
+      'sync.outcome.deleted_from_left': '已从 {{left}} 删除',
+      'sync.outcome.conflict': '冲突',
+      'sync.outcome.invalid': '无效',
+      'sync.deleted': '（已删除）',
+      'sync.report.dropped': '未保留',
+      'sync.report.renamed': '已改名',
+      'sync.report.inferred': '推断得出',
+      'sync.report.restored': '按上次转换恢复',
+      'sync.keep': '保留 {{tool}} 版本',
+      'sync.history': '历史记录',
+      'sync.historyEmpty': '暂无变更记录。',
+      'sync.undo': '撤销',
+      'sync.undoAnyway': '仍要撤销并覆盖之后的修改吗？',
       
       // Backup
       'backup.title': 'MCP 备份',
@@ -8,6 +8,8 @@ import {
   Server,
   Globe
 } from 'lucide-react';
+import { getErrorMessage, MCP_SOURCE_LABELS } from '../types';
+import type { McpSource } from '../types';
 
 // MCP Backup Info type matching Rust struct
 interface MCPBackupInfo {
@@ -31,7 +33,7 @@ interface BackupDetail extends MCPBackupInfo {
 }
 
 interface BackupListPageProps {
-  source: 'opencode' | 'claude';
+  source: McpSource;
 }
 
 export function BackupListPage({ source }: BackupListPageProps) {
@@ -92,7 +94,7 @@ export function BackupListPage({ source }: BackupListPageProps) {
       await loadBackup();
     } catch (error) {
       console.error('Restore failed:', error);
-      alert(`${t('backup.restoreError')}: ${error}`);
+      alert(`${t('backup.restoreError')}: ${getErrorMessage(error, t('status.error'))}`);
     } finally {
       setRestoring(false);
     }
@@ -109,7 +111,7 @@ export function BackupListPage({ source }: BackupListPageProps) {
       alert(result);
     } catch (error) {
       console.error('Restore MCP failed:', error);
-      alert(`${t('backup.restoreError')}: ${error}`);
+      alert(`${t('backup.restoreError')}: ${getErrorMessage(error, t('status.error'))}`);
     } finally {
       setRestoringMcp(null);
     }
@@ -148,7 +150,7 @@ export function BackupListPage({ source }: BackupListPageProps) {
       <div className="flex items-center justify-between mb-8">
         <div className="flex items-center gap-4">
           <h1 className={`text-2xl font-bold ${theme === 'light' ? 'text-slate-800' : 'text-slate-100'}`}>
-            {source === 'opencode' ? 'OpenCode' : 'Claude Code'} {t('backup.title')}
+            {MCP_SOURCE_LABELS[source]} {t('backup.title')}
           </h1>
         </div>
         {backup && (
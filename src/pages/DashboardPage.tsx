@@ -21,8 +21,8 @@ export function DashboardPage() {
     loadMCPList();
     getConfigPaths().then(paths => {
       setConfigPaths({
-        opencode: paths.opencode,
-        claude: paths.claude
+        opencode: paths.opencode.path,
+        claude: paths.claude.path
       });
     });
   }, [loadMCPList, getConfigPaths]);
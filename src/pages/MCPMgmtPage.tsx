@@ -11,11 +11,13 @@ import {
   Server,
   Globe,
   X,
-  AlertCircle
+  AlertCircle,
+  FolderPlus
 } from 'lucide-react';
-import type { MCPItem } from '../types';
-import { parseMCPConfig, getMCPDisplayType, getMCPDescription } from '../types';
+import type { MCPItem, MCPScope, McpSource, ServerComparison } from '../types';
+import { parseMCPConfig, getMCPDisplayType, getMCPDescription, getErrorMessage, isConfigError, conversionReportLines, MCP_SOURCE_LABELS, TOGGLEABLE_SOURCES, PROJECT_SOURCES } from '../types';
 import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
 
 // MCP Backup Info type matching Rust struct
 interface MCPBackupInfo {
@@ -27,13 +29,34 @@ interface MCPBackupInfo {
   created_at: string;
 }
 
+/// Hint shown when a tool has no servers
+const EMPTY_HINTS: Record<McpSource, string> = {
+  opencode: 'mcp.noOpenCodeMcp',
+  claude: 'mcp.noClaudeMcp',
+  'claude-desktop': 'mcp.noClaudeDesktopMcp',
+  cursor: 'mcp.noCursorMcp',
+};
+
 interface MCPMgmtPageProps {
-  source: 'opencode' | 'claude';
+  source: McpSource;
 }
 
 export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
   const { t } = useTranslation();
-  const { mcpList, loading, error, loadMCPList, deleteMCP, syncMCP } = useConfigStore();
+  const {
+    mcpList,
+    projects,
+    loading,
+    error,
+    loadMCPList,
+    loadProjects,
+    addProject,
+    removeProject,
+    deleteMCP,
+    toggleMCP,
+    syncMCP,
+    compareMCP,
+  } = useConfigStore();
   const { theme } = useSettingsStore();
 
   const [showAddModal, setShowAddModal] = useState(false);
@@ -47,10 +70,40 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
   // Load configs when source changes
   useEffect(() => {
     loadMCPList();
-  }, [loadMCPList, source]);
+    loadProjects();
+  }, [loadMCPList, loadProjects, source]);
+
+  // Compare same-named user scope servers with Claude Code (OpenCode for Claude Code itself)
+  const compareWith: McpSource = source === 'claude' ? 'opencode' : 'claude';
+  const [comparisons, setComparisons] = useState<Record<string, ServerComparison>>({});
+  useEffect(() => {
+    compareMCP({ from: source, to: compareWith })
+      .then((list) => setComparisons(Object.fromEntries(list.map((c) => [c.name, c]))))
+      .catch((error) => console.error('Failed to compare MCPs:', error));
+  }, [compareMCP, mcpList, source, compareWith]);
+
+  // Filter MCP list by source (Claude includes project .mcp.json servers)
+  const filteredMcpList = source === 'claude'
+    ? [...mcpList.claude, ...mcpList.project, ...mcpList.plugin]
+    : mcpList[source] ?? [];
+
+  // Every other tool a server can be synced to
+  const syncTargets = (Object.keys(MCP_SOURCE_LABELS) as McpSource[]).filter((target) => target !== source);
 
-  // Filter MCP list by source
-  const filteredMcpList = source === 'opencode' ? mcpList.opencode : mcpList.claude;
+  const handleAddProject = async () => {
+    try {
+      const selected = await open({
+        directory: true,
+        multiple: false,
+        title: t('mcp.selectProject'),
+      });
+      if (selected) {
+        await addProject(selected as string);
+      }
+    } catch (error) {
+      console.error('Failed to add project:', error);
+    }
+  };
 
   const handleRefresh = async () => {
     setRefreshing(true);
@@ -60,17 +113,29 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
 
   const handleDelete = async (mcp: MCPItem) => {
     if (confirm(`${t('mcp.delete')} ${mcp.name}?`)) {
-      await deleteMCP(mcp.name, mcp.source);
+      await deleteMCP(mcp.name, mcp.source, mcp.scope);
     }
   };
 
-  const handleSync = async (mcp: MCPItem) => {
-    const targetSource = source === 'opencode' ? 'claude' : 'opencode';
+  const handleToggle = async (mcp: MCPItem) => {
+    try {
+      await toggleMCP(mcp.name, mcp.source, !mcp.enabled, mcp.scope);
+    } catch (error) {
+      console.error('Toggle failed:', error);
+    }
+  };
+
+  const handleSync = async (mcp: MCPItem, targetSource: McpSource) => {
     setSyncingMcp(mcp.name);
     try {
-      await syncMCP(mcp.name, mcp.source, targetSource, mcp.config);
+      const report = await syncMCP(mcp.name, mcp.source, targetSource, mcp.config, undefined, false, mcp.scope);
+      const lines = conversionReportLines(report, t);
+      if (lines.length > 0) {
+        alert(`${t('mcp.syncReport')}\n\n${lines.join('\n')}`);
+      }
     } catch (error) {
       console.error('Sync failed:', error);
+      alert(`${t('status.error')}: ${getErrorMessage(error, t('status.error'))}`);
     } finally {
       setSyncingMcp(null);
     }
@@ -83,12 +148,11 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
     setBackuping(true);
     
     try {
-      const command = source === 'opencode' ? 'backup_opencode_mcps' : 'backup_claude_mcps';
-      const result = await invoke<MCPBackupInfo>(command);
+      const result = await invoke<MCPBackupInfo>('backup_mcps', { source });
       alert(`${t('action.backup')} ${t('status.success')}\n\n${result.mcp_count} MCPs backed up`);
     } catch (error) {
       console.error('Backup failed:', error);
-      alert(`${t('action.backup')} ${t('status.error')}: ${error}`);
+      alert(`${t('action.backup')} ${t('status.error')}: ${getErrorMessage(error, t('status.error'))}`);
     } finally {
       backupingRef.current = false;
       setBackuping(false);
@@ -117,10 +181,19 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
             <RefreshCw size={16} className={refreshing || loading ? 'animate-spin' : ''} />
           </button>
           <span className={`text-sm ${theme === 'light' ? 'text-slate-500' : 'text-slate-400'}`}>
-            {filteredMcpList.length} {source === 'opencode' ? 'OpenCode' : 'Claude Code'} MCPs
+            {filteredMcpList.length} {MCP_SOURCE_LABELS[source]} MCPs
           </span>
         </div>
         <div className="flex items-center gap-3">
+          {PROJECT_SOURCES.includes(source) && (
+            <button
+              onClick={handleAddProject}
+              className="btn-secondary flex items-center gap-2"
+            >
+              <FolderPlus size={18} />
+              {t('mcp.addProject')}
+            </button>
+          )}
           <button
             onClick={handleBackup}
             disabled={backuping}
@@ -147,6 +220,27 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
         </div>
       )}
 
+      {/* Registered Projects (.mcp.json) */}
+      {PROJECT_SOURCES.includes(source) && projects.length > 0 && (
+        <div className="mb-6 flex flex-wrap items-center gap-2">
+          <span className={`text-sm ${theme === 'light' ? 'text-slate-500' : 'text-slate-400'}`}>
+            {t('mcp.projects')}:
+          </span>
+          {projects.map((project) => (
+            <span key={project} className="tag font-mono text-xs flex items-center gap-1" title={project}>
+              {project}
+              <button
+                onClick={() => removeProject(project)}
+                className="hover:text-red-500 transition-colors"
+                title={t('mcp.removeProject')}
+              >
+                <X size={12} />
+              </button>
+            </span>
+          ))}
+        </div>
+      )}
+
       {/* Loading State */}
       {loading || refreshing ? (
         <div className={`py-12 text-center ${theme === 'light' ? 'text-slate-400' : 'text-slate-500'}`}>
@@ -158,9 +252,7 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
           <Server size={48} className={`mx-auto mb-4 opacity-50`} />
           <p className="text-lg mb-2">{t('mcp.noMcp')}</p>
           <p className={`text-sm ${theme === 'light' ? 'text-slate-400' : 'text-slate-500'}`}>
-            {source === 'opencode'
-              ? t('mcp.noOpenCodeMcp')
-              : t('mcp.noClaudeMcp')}
+            {t(EMPTY_HINTS[source])}
           </p>
           <button
             onClick={() => setShowAddModal(true)}
@@ -179,7 +271,7 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
 
             return (
               <div
-                key={`${mcp.source}-${mcp.name}`}
+                key={`${mcp.source}-${mcp.scope.kind}-${mcp.scope.kind === 'user' ? '' : mcp.scope.path}-${mcp.name}`}
                 className={`card group relative overflow-hidden`}
               >
                 <div className="flex justify-between items-start mb-4">
@@ -190,7 +282,14 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
                       <Server size={24} />
                     )}
                   </div>
-                  <span
+                  <button
+                    onClick={() => handleToggle(mcp)}
+                    disabled={mcp.read_only || !TOGGLEABLE_SOURCES.includes(mcp.source)}
+                    title={
+                      mcp.read_only ? t('mcp.readOnly')
+                        : !TOGGLEABLE_SOURCES.includes(mcp.source) ? t('mcp.cannotDisable', { tool: MCP_SOURCE_LABELS[mcp.source] })
+                          : mcp.enabled ? t('mcp.disable') : t('mcp.enable')
+                    }
                     className={`
                       tag font-bold
                       ${mcp.enabled ? 'tag-success' : 'tag-warning'}
@@ -198,42 +297,70 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
                   >
                     <span className={`status-dot ${mcp.enabled ? 'status-active' : 'status-paused'}`}></span>
                     {mcp.enabled ? 'ACTIVE' : 'PAUSED'}
-                  </span>
+                  </button>
                 </div>
 
                 <h3 className={`font-bold text-lg mb-2 ${theme === 'light' ? 'text-slate-800' : 'text-slate-100'}`}>
                   {mcp.name}
+                  {mcp.scope.kind === 'user' && comparisons[mcp.name]?.status === 'identical' && (
+                    <span className="ml-2 text-xs font-normal text-emerald-500">{t('mcp.sameInOther', { tool: MCP_SOURCE_LABELS[compareWith] })}</span>
+                  )}
+                  {mcp.scope.kind === 'user' && comparisons[mcp.name]?.status === 'differs' && (
+                    <span
+                      className="ml-2 text-xs font-normal text-amber-500"
+                      title={comparisons[mcp.name].fields?.map((f) => f.path).join(', ')}
+                    >
+                      {t('mcp.differsInOther', { tool: MCP_SOURCE_LABELS[compareWith] })}
+                    </span>
+                  )}
                 </h3>
+                {mcp.scope.kind === 'plugin' ? (
+                  <p className="text-xs font-mono truncate mb-2 text-amber-500" title={mcp.scope.path}>
+                    {t('mcp.scopePlugin')}: {mcp.scope.plugin} ({t('mcp.readOnly')})
+                  </p>
+                ) : mcp.scope.kind !== 'user' && (
+                  <p className="text-xs font-mono truncate mb-2 text-indigo-500" title={mcp.scope.path}>
+                    {mcp.scope.kind === 'local' ? t('mcp.scopeLocal') : t('mcp.scopeProject')}: {mcp.scope.path}
+                  </p>
+                )}
                 <p className={`text-sm leading-relaxed mb-8 h-10 overflow-hidden ${theme === 'light' ? 'text-slate-500' : 'text-slate-400'}`}>
                   {description || `(${displayType})`}
                 </p>
 
                 <div className={`flex items-center justify-between pt-4 border-t ${theme === 'light' ? 'border-slate-100' : 'border-slate-700'}`}>
                   <div className={`flex items-center gap-3 ${theme === 'light' ? 'text-slate-400' : 'text-slate-500'}`}>
-                    <button
-                      onClick={() => handleSync(mcp)}
-                      className={`hover:text-indigo-600 transition-colors flex items-center gap-1.5`}
-                      disabled={syncingMcp === mcp.name}
-                    >
-                      <RefreshCw size={16} className={syncingMcp === mcp.name ? 'animate-spin' : ''} />
-                      <span className="text-[11px] font-medium">
-                        {source === 'opencode' ? t('mcp.syncToClaude') : t('mcp.syncToOpenCode')}
-                      </span>
-                    </button>
-                    <button
-                      onClick={() => setEditingMcp(mcp)}
-                      className={`hover:text-indigo-600 transition-colors`}
-                      title={t('mcp.edit')}
-                    >
-                      <Edit2 size={16} />
-                    </button>
-                    <button
-                      onClick={() => handleDelete(mcp)}
-                      className={`hover:text-red-500 transition-colors`}
-                      title={t('mcp.delete')}
-                    >
-                      <Trash2 size={16} />
-                    </button>
+                    {syncTargets.map((target) => (
+                      <button
+                        key={target}
+                        onClick={() => handleSync(mcp, target)}
+                        className={`hover:text-indigo-600 transition-colors flex items-center gap-1.5`}
+                        disabled={syncingMcp === mcp.name}
+                        title={t('mcp.syncTo', { tool: MCP_SOURCE_LABELS[target] })}
+                      >
+                        <RefreshCw size={16} className={syncingMcp === mcp.name ? 'animate-spin' : ''} />
+                        <span className="text-[11px] font-medium">
+                          {MCP_SOURCE_LABELS[target]}
+                        </span>
+                      </button>
+                    ))}
+                    {!mcp.read_only && (
+                      <>
+                        <button
+                          onClick={() => setEditingMcp(mcp)}
+                          className={`hover:text-indigo-600 transition-colors`}
+                          title={t('mcp.edit')}
+                        >
+                          <Edit2 size={16} />
+                        </button>
+                        <button
+                          onClick={() => handleDelete(mcp)}
+                          className={`hover:text-red-500 transition-colors`}
+                          title={t('mcp.delete')}
+                        >
+                          <Trash2 size={16} />
+                        </button>
+                      </>
+                    )}
                   </div>
                   <button
                     onClick={() => handleViewLog(mcp)}
@@ -253,17 +380,28 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
         <MCPEditModal
           mcp={editingMcp}
           source={source}
+          projects={projects}
           onClose={() => {
             setShowAddModal(false);
             setEditingMcp(null);
           }}
-          onSave={async (name, configJson, description) => {
-            if (editingMcp) {
-              const { updateMCP } = useConfigStore.getState();
-              await updateMCP(name, configJson, editingMcp.source, description);
-            } else {
-              const { addMCP } = useConfigStore.getState();
-              await addMCP(name, configJson, source, description);
+          onSave={async (name, configJson, description, scope) => {
+            const save = async (force: boolean) => {
+              if (editingMcp) {
+                const { updateMCP } = useConfigStore.getState();
+                await updateMCP(name, configJson, editingMcp.source, description, editingMcp.scope, force);
+              } else {
+                const { addMCP } = useConfigStore.getState();
+                await addMCP(name, configJson, source, description, scope, force);
+              }
+            };
+            try {
+              await save(false);
+            } catch (error) {
+              // Let the user write an entry the target tool may reject
+              if (!isConfigError(error) || error.code !== 'validation_error') throw error;
+              if (!confirm(`${t('mcp.validationFailed')}\n\n${(error.errors ?? []).join('\n')}\n\n${t('mcp.saveAnyway')}`)) return;
+              await save(true);
             }
             setShowAddModal(false);
             setEditingMcp(null);
@@ -284,19 +422,28 @@ export function MCPMgmtPage({ source }: MCPMgmtPageProps) {
 
 interface MCPEditModalProps {
   mcp: MCPItem | null;
-  source: 'opencode' | 'claude';
+  source: McpSource;
   onClose: () => void;
-  onSave: (name: string, configJson: string, description?: string) => Promise<void>;
+  projects: string[];
+  onSave: (name: string, configJson: string, description?: string, scope?: MCPScope) => Promise<void>;
 }
 
-function MCPEditModal({ mcp, source, onClose, onSave }: MCPEditModalProps) {
+function MCPEditModal({ mcp, source, projects, onClose, onSave }: MCPEditModalProps) {
   const { t } = useTranslation();
   const { theme } = useSettingsStore();
   const [name, setName] = useState(mcp?.name || '');
   const [configJson, setConfigJson] = useState(
-    mcp?.config || JSON.stringify({ type: 'remote', url: '', enabled: true }, null, 2)
+    mcp?.config || JSON.stringify(
+      source === 'claude-desktop' || source === 'cursor'
+        ? { command: '', args: [], env: {} }  // Plain stdio entry, no type field
+        : { type: 'remote', url: '', enabled: true },
+      null,
+      2
+    )
   );
   const [description, setDescription] = useState(mcp?.description || '');
+  const [scopeKind, setScopeKind] = useState<MCPScope['kind']>(mcp?.scope.kind || 'user');
+  const [projectPath, setProjectPath] = useState(mcp && mcp.scope.kind !== 'user' ? mcp.scope.path : '');
   const [saving, setSaving] = useState(false);
   const [jsonError, setJsonError] = useState<string | null>(null);
 
@@ -332,7 +479,10 @@ function MCPEditModal({ mcp, source, onClose, onSave }: MCPEditModalProps) {
 
     setSaving(true);
     try {
-      await onSave(name.trim(), configJson, description.trim() || undefined);
+      const scope: MCPScope = scopeKind !== 'user' && projectPath.trim()
+        ? { kind: scopeKind, path: projectPath.trim() }
+        : { kind: 'user' };
+      await onSave(name.trim(), configJson, description.trim() || undefined, scope);
     } finally {
       setSaving(false);
     }
@@ -351,7 +501,7 @@ function MCPEditModal({ mcp, source, onClose, onSave }: MCPEditModalProps) {
         </div>
 
         <div className={`mb-4 px-3 py-2 rounded-lg text-sm ${theme === 'light' ? 'bg-indigo-50 text-indigo-600' : 'bg-indigo-900/30 text-indigo-400'}`}>
-          {source === 'opencode' ? 'OpenCode' : 'Claude Code'} MCP
+          {MCP_SOURCE_LABELS[source]} MCP
         </div>
 
         <form onSubmit={handleSubmit} className="space-y-4">
@@ -383,6 +533,43 @@ function MCPEditModal({ mcp, source, onClose, onSave }: MCPEditModalProps) {
             />
           </div>
 
+          {PROJECT_SOURCES.includes(source) && (
+            <div>
+              <label className={`block text-sm font-medium mb-2 ${theme === 'light' ? 'text-slate-700' : 'text-slate-300'}`}>
+                {t('mcp.scope')}
+              </label>
+              <div className="flex gap-3">
+                <select
+                  value={scopeKind}
+                  onChange={(e) => setScopeKind(e.target.value as MCPScope['kind'])}
+                  className="input"
+                  disabled={!!mcp} // Scope of an existing MCP is fixed
+                >
+                  <option value="user">{t('mcp.scopeUser')}</option>
+                  {source === 'claude' && <option value="local">{t('mcp.scopeLocal')}</option>}
+                  <option value="project">{t('mcp.scopeProject')}</option>
+                </select>
+                {scopeKind !== 'user' && (
+                  <input
+                    type="text"
+                    value={projectPath}
+                    onChange={(e) => setProjectPath(e.target.value)}
+                    placeholder={t('mcp.projectPathPlaceholder')}
+                    className="input flex-1 font-mono text-sm"
+                    list="mcp-projects"
+                    required
+                    disabled={!!mcp}
+                  />
+                )}
+                <datalist id="mcp-projects">
+                  {projects.map((project) => (
+                    <option key={project} value={project} />
+                  ))}
+                </datalist>
+              </div>
+            </div>
+          )}
+
           <div>
             <div className="flex items-center justify-between mb-2">
               <label className={`block text-sm font-medium ${theme === 'light' ? 'text-slate-700' : 'text-slate-300'}`}>
@@ -470,7 +657,7 @@ function MCPLogModal({ mcp, onClose }: MCPLogModalProps) {
             {displayType.toUpperCase()}
           </span>
           <span className={`px-2 py-1 rounded ${theme === 'light' ? 'bg-slate-100' : 'bg-slate-700'}`}>
-            {mcp.source === 'opencode' ? 'OpenCode' : 'Claude Code'}
+            {MCP_SOURCE_LABELS[mcp.source]}
           </span>
         </div>
 
@@ -1,78 +1,151 @@
-import { useState, useEffect } from 'react';
+import { useState, useEffect, useCallback } from 'react';
 import { useTranslation } from 'react-i18next';
 import { useConfigStore } from '../stores/configStore';
 import { useSettingsStore } from '../stores/settingsStore';
 import {
   ArrowRight,
+  ArrowLeftRight,
   CheckCircle,
   AlertCircle
 } from 'lucide-react';
+import { MCP_SOURCE_LABELS, conversionReportLines, getErrorMessage } from '../types';
+import type { HistoryEntry, McpSource, MergeItem, SyncDirection, SyncDirectionPreview, SyncReport, SyncStrategy } from '../types';
+
+const STRATEGIES: SyncStrategy[] = ['only-missing', 'overwrite', 'skip-on-conflict', 'prefer-newer'];
+const SOURCES = Object.keys(MCP_SOURCE_LABELS) as McpSource[];
 
 export function SyncCenterPage() {
   const { t } = useTranslation();
-  const { mcpList, loadMCPList, syncMCP, loading } = useConfigStore();
+  const {
+    loadMCPList,
+    previewSync,
+    syncAll,
+    syncBidirectional,
+    syncMCP,
+    getSyncHistory,
+    undoHistoryEntry,
+    loading,
+  } = useConfigStore();
   const { theme } = useSettingsStore();
   const [syncing, setSyncing] = useState(false);
   const [syncResult, setSyncResult] = useState<{ success: boolean; message: string } | null>(null);
+  const [pair, setPair] = useState<SyncDirection>({ from: 'opencode', to: 'claude' });
+  const [toTarget, setToTarget] = useState<SyncDirectionPreview | null>(null);
+  const [toSource, setToSource] = useState<SyncDirectionPreview | null>(null);
+  const [strategy, setStrategy] = useState<SyncStrategy>('only-missing');
+  const [dryRun, setDryRun] = useState(false);
+  const [report, setReport] = useState<SyncReport | null>(null);
+  const [mergeItems, setMergeItems] = useState<MergeItem[]>([]);
+  const [mergeSides, setMergeSides] = useState<{ left: McpSource; right: McpSource } | null>(null);
+  const [history, setHistory] = useState<HistoryEntry[]>([]);
 
-  // Load MCP list on mount
-  useEffect(() => {
-    loadMCPList();
-  }, [loadMCPList]);
+  const reverse: SyncDirection = { from: pair.to, to: pair.from };
+  const label = (source: McpSource) => MCP_SOURCE_LABELS[source];
 
-  // Calculate diff for display
-  const opencodeMcpNames = new Set(mcpList.opencode.map(m => m.name));
-  const claudeMcpNames = new Set(mcpList.claude.map(m => m.name));
+  // Compute what each direction would change, after format conversion
+  const loadPreview = useCallback(async () => {
+    try {
+      const [targetPreview, sourcePreview] = await Promise.all([
+        previewSync(pair),
+        previewSync({ from: pair.to, to: pair.from }),
+      ]);
+      setToTarget(targetPreview);
+      setToSource(sourcePreview);
+      setHistory(await getSyncHistory(50));
+    } catch (error) {
+      setSyncResult({ success: false, message: getErrorMessage(error, t('sync.failed')) });
+    }
+  }, [pair, previewSync, getSyncHistory, t]);
+
+  // Pick one tool of the pair; picking the other tool's swaps them
+  const choose = (side: keyof SyncDirection, source: McpSource) => {
+    const other = side === 'from' ? 'to' : 'from';
+    setPair(source === pair[other] ? { ...pair, [side]: source, [other]: pair[side] } : { ...pair, [side]: source });
+    setReport(null);
+    setMergeItems([]);
+  };
 
-  const itemsOnlyInOpencode = [...opencodeMcpNames].filter(n => !claudeMcpNames.has(n));
-  const itemsOnlyInClaude = [...claudeMcpNames].filter(n => !opencodeMcpNames.has(n));
+  // Load MCP list and preview on mount
+  useEffect(() => {
+    loadMCPList();
+    loadPreview();
+  }, [loadMCPList, loadPreview]);
 
-  const handleSyncToClaude = async () => {
-    if (itemsOnlyInOpencode.length === 0) return;
+  const pending = (preview: SyncDirectionPreview | null) =>
+    preview ? preview.added.length + preview.updated.length : 0;
 
+  const handleSync = async (direction: SyncDirection) => {
     setSyncing(true);
     setSyncResult(null);
+    setReport(null);
+    setMergeItems([]);
     try {
-      for (const name of itemsOnlyInOpencode) {
-        const mcp = mcpList.opencode.find(m => m.name === name);
-        if (mcp) {
-          await syncMCP(name, 'opencode', 'claude', mcp.config);
-        }
+      const result = await syncAll(direction, strategy, undefined, dryRun);
+      setReport(result);
+      if (!result.dry_run) {
+        await loadPreview();
       }
-      setSyncResult({ success: true, message: t('sync.success') });
+      const failed = result.items.some((item) => item.action === 'invalid' || item.action === 'conflict');
+      setSyncResult({ success: !failed, message: failed ? t('sync.partial') : t('sync.success') });
     } catch (error) {
       setSyncResult({
         success: false,
-        message: error instanceof Error ? error.message : t('sync.failed')
+        message: getErrorMessage(error, t('sync.failed'))
       });
     } finally {
       setSyncing(false);
     }
   };
 
-  const handleSyncToOpenCode = async () => {
-    if (itemsOnlyInClaude.length === 0) return;
-
+  const handleSyncBoth = async () => {
     setSyncing(true);
     setSyncResult(null);
+    setReport(null);
     try {
-      for (const name of itemsOnlyInClaude) {
-        const mcp = mcpList.claude.find(m => m.name === name);
-        if (mcp) {
-          await syncMCP(name, 'claude', 'opencode', mcp.config);
-        }
+      const result = await syncBidirectional(pair, undefined, dryRun);
+      setMergeItems(result.items.filter((item) => item.outcome !== 'unchanged'));
+      setMergeSides({ left: result.left, right: result.right });
+      if (!result.dry_run) {
+        await loadPreview();
       }
-      setSyncResult({ success: true, message: t('sync.success') });
+      const failed = result.items.some((item) => item.outcome === 'invalid' || item.outcome === 'conflict');
+      setSyncResult({ success: !failed, message: failed ? t('sync.partial') : t('sync.success') });
     } catch (error) {
       setSyncResult({
         success: false,
-        message: error instanceof Error ? error.message : t('sync.failed')
+        message: getErrorMessage(error, t('sync.failed'))
       });
     } finally {
       setSyncing(false);
     }
   };
 
+  const handleUndo = async (entry: HistoryEntry) => {
+    if (!confirm(`${t('sync.undo')} ${entry.action} ${entry.name}?`)) return;
+    try {
+      await undoHistoryEntry(entry.id);
+    } catch (error) {
+      // The entry changed again since; let the user overwrite it explicitly
+      if (!confirm(`${getErrorMessage(error, t('sync.failed'))}\n\n${t('sync.undoAnyway')}`)) return;
+      await undoHistoryEntry(entry.id, true);
+    }
+    await loadPreview();
+  };
+
+  // Resolve a conflict by copying the chosen side over the other
+  const handleResolve = async (item: MergeItem, keep: 'left' | 'right') => {
+    const config = item[keep];
+    if (config === undefined || !mergeSides) return;
+    const other = keep === 'left' ? 'right' : 'left';
+    try {
+      await syncMCP(item.name, mergeSides[keep], mergeSides[other], JSON.stringify(config));
+      setMergeItems((items) => items.filter((i) => i.name !== item.name));
+      await loadPreview();
+    } catch (error) {
+      setSyncResult({ success: false, message: getErrorMessage(error, t('sync.failed')) });
+    }
+  };
+
   return (
     <div className="p-6 space-y-6 animate-fade-in">
       {/* Header */}
@@ -84,10 +157,46 @@ export function SyncCenterPage() {
       {/* Sync Direction */}
       <div className="card">
         <h3 className="font-semibold text-white mb-4">{t('sync.direction')}</h3>
+        <div className="flex flex-wrap items-center gap-4 mb-4 text-sm text-slate-400">
+          <label className="flex items-center gap-2">
+            {t('sync.tools')}
+            {(['from', 'to'] as const).map((side) => (
+              <select
+                key={side}
+                value={pair[side]}
+                onChange={(e) => choose(side, e.target.value as McpSource)}
+                className="input py-1"
+              >
+                {SOURCES.map((source) => (
+                  <option key={source} value={source}>{label(source)}</option>
+                ))}
+              </select>
+            ))}
+          </label>
+          <label className="flex items-center gap-2">
+            {t('sync.strategyLabel')}
+            <select
+              value={strategy}
+              onChange={(e) => setStrategy(e.target.value as SyncStrategy)}
+              className="input py-1"
+            >
+              {STRATEGIES.map((s) => (
+                <option key={s} value={s}>{t(`sync.strategy.${s}`)}</option>
+              ))}
+            </select>
+          </label>
+          <label className="flex items-center gap-2">
+            <input type="checkbox" checked={dryRun} onChange={(e) => setDryRun(e.target.checked)} />
+            {t('sync.dryRun')}
+          </label>
+        </div>
+        {strategy === 'prefer-newer' && (
+          <p className="text-xs text-slate-400 mb-4">{t('sync.preferNewerHint')}</p>
+        )}
         <div className="flex flex-col sm:flex-row gap-4">
           <button
-            onClick={handleSyncToClaude}
-            disabled={loading || syncing || itemsOnlyInOpencode.length === 0}
+            onClick={() => handleSync(pair)}
+            disabled={loading || syncing || pending(toTarget) === 0}
             className={`
               flex-1 p-4 rounded-lg border-2 transition-all text-left
               ${theme === 'light' ? 'border-light-border hover:border-primary/50' : 'border-dark-border hover:border-primary/50'}
@@ -95,18 +204,18 @@ export function SyncCenterPage() {
             `}
           >
             <div className="flex items-center justify-between mb-2">
-              <span className="font-semibold text-white">OpenCode</span>
+              <span className="font-semibold text-white">{label(pair.from)}</span>
               <ArrowRight size={20} className="text-primary" />
-              <span className="font-semibold text-white">Claude Code</span>
+              <span className="font-semibold text-white">{label(pair.to)}</span>
             </div>
             <p className="text-sm text-slate-400">
-              {itemsOnlyInOpencode.length} items to sync
+              {pending(toTarget)} items to sync
             </p>
           </button>
 
           <button
-            onClick={handleSyncToOpenCode}
-            disabled={loading || syncing || itemsOnlyInClaude.length === 0}
+            onClick={() => handleSync(reverse)}
+            disabled={loading || syncing || pending(toSource) === 0}
             className={`
               flex-1 p-4 rounded-lg border-2 transition-all text-left
               ${theme === 'light' ? 'border-light-border hover:border-secondary/50' : 'border-dark-border hover:border-secondary/50'}
@@ -114,14 +223,31 @@ export function SyncCenterPage() {
             `}
           >
             <div className="flex items-center justify-between mb-2">
-              <span className="font-semibold text-white">Claude Code</span>
+              <span className="font-semibold text-white">{label(pair.to)}</span>
               <ArrowRight size={20} className="text-secondary" />
-              <span className="font-semibold text-white">OpenCode</span>
+              <span className="font-semibold text-white">{label(pair.from)}</span>
             </div>
             <p className="text-sm text-slate-400">
-              {itemsOnlyInClaude.length} items to sync
+              {pending(toSource)} items to sync
             </p>
           </button>
+
+          <button
+            onClick={handleSyncBoth}
+            disabled={loading || syncing}
+            className={`
+              flex-1 p-4 rounded-lg border-2 transition-all text-left
+              ${theme === 'light' ? 'border-light-border hover:border-primary/50' : 'border-dark-border hover:border-primary/50'}
+              disabled:opacity-50 disabled:cursor-not-allowed
+            `}
+          >
+            <div className="flex items-center justify-between mb-2">
+              <span className="font-semibold text-white">{label(pair.from)}</span>
+              <ArrowLeftRight size={20} className="text-primary" />
+              <span className="font-semibold text-white">{label(pair.to)}</span>
+            </div>
+            <p className="text-sm text-slate-400">{t('sync.bidirectional')}</p>
+          </button>
         </div>
       </div>
 
@@ -138,6 +264,51 @@ export function SyncCenterPage() {
               {syncResult.message}
             </span>
           </div>
+          {report && (
+            <div className="mt-3 space-y-1">
+              {report.dry_run && <p className="text-xs text-warning">{t('sync.dryRunNote')}</p>}
+              {report.items.map((item) => (
+                <div key={item.name} className="text-sm text-slate-400 font-mono">
+                  [{t(`sync.action.${item.action}`)}] {item.name}
+                  {item.message && <span className="text-xs"> — {item.message}</span>}
+                  {item.report && conversionReportLines(item.report, t).map((line) => (
+                    <div key={line} className="text-xs pl-4">{line}</div>
+                  ))}
+                </div>
+              ))}
+            </div>
+          )}
+          {mergeSides && mergeItems.length > 0 && (
+            <div className="mt-3 space-y-2">
+              {mergeItems.map((item) => (
+                <div key={item.name} className="text-sm text-slate-400">
+                  <span className="font-mono">
+                    [{t(`sync.outcome.${item.outcome}`, { left: label(mergeSides.left), right: label(mergeSides.right) })}] {item.name}
+                  </span>
+                  {item.message && <span className="text-xs"> — {item.message}</span>}
+                  {item.report && conversionReportLines(item.report, t).map((line) => (
+                    <div key={line} className="text-xs pl-4">{line}</div>
+                  ))}
+                  {item.outcome === 'conflict' && (
+                    <div className="grid grid-cols-2 gap-2 mt-1">
+                      {(['left', 'right'] as const).map((side) => (
+                        <div key={side} className="text-xs">
+                          <pre className="font-mono whitespace-pre-wrap p-2 rounded bg-black/20">
+                            {item[side] === undefined ? t('sync.deleted') : JSON.stringify(item[side], null, 2)}
+                          </pre>
+                          {item[side] !== undefined && (
+                            <button onClick={() => handleResolve(item, side)} className="text-primary mt-1">
+                              {t('sync.keep', { tool: label(mergeSides[side]) })}
+                            </button>
+                          )}
+                        </div>
+                      ))}
+                    </div>
+                  )}
+                </div>
+              ))}
+            </div>
+          )}
         </div>
       )}
 
@@ -145,42 +316,97 @@ export function SyncCenterPage() {
       <div className="card">
         <h3 className="font-semibold text-white mb-4">{t('sync.previewTitle')}</h3>
 
-        {/* OpenCode Only */}
-        {itemsOnlyInOpencode.length > 0 && (
-          <div className="mb-4">
-            <h4 className="text-sm font-medium text-success mb-2">
-              Only in OpenCode ({itemsOnlyInOpencode.length})
-            </h4>
-            <div className="space-y-1">
-              {itemsOnlyInOpencode.map((item, i) => (
-                <div key={i} className="text-sm text-slate-400 pl-3 border-l-2 border-success">
-                  {item}
-                </div>
-              ))}
-            </div>
+        <DirectionPreview title={`${label(pair.from)} → ${label(pair.to)}`} preview={toTarget} />
+        <DirectionPreview title={`${label(pair.to)} → ${label(pair.from)}`} preview={toSource} />
+      </div>
+
+      {/* History */}
+      <div className="card">
+        <h3 className="font-semibold text-white mb-4">{t('sync.history')}</h3>
+        {history.length === 0 ? (
+          <p className="text-slate-400 text-sm">{t('sync.historyEmpty')}</p>
+        ) : (
+          <div className="space-y-1">
+            {history.map((entry) => (
+              <div key={entry.id} className="flex items-center justify-between text-sm text-slate-400">
+                <span className="font-mono truncate">
+                  {new Date(entry.timestamp).toLocaleString()} [{entry.action}] {entry.name}{' '}
+                  {entry.source === entry.target ? entry.target : `${entry.source} → ${entry.target}`}
+                </span>
+                <button onClick={() => handleUndo(entry)} className="text-primary shrink-0 ml-2">
+                  {t('sync.undo')}
+                </button>
+              </div>
+            ))}
           </div>
         )}
+      </div>
+    </div>
+  );
+}
+
+/// Added, updated (with field-level diffs) and removed servers for one direction
+function DirectionPreview({ title, preview }: { title: string; preview: SyncDirectionPreview | null }) {
+  const { t } = useTranslation();
+  if (!preview) return null;
+
+  const { added, updated, removed } = preview;
+  const format = (value: unknown) => (value === undefined ? '∅' : JSON.stringify(value));
 
-        {/* Claude Only */}
-        {itemsOnlyInClaude.length > 0 && (
-          <div className="mb-4">
-            <h4 className="text-sm font-medium text-warning mb-2">
-              Only in Claude Code ({itemsOnlyInClaude.length})
-            </h4>
-            <div className="space-y-1">
-              {itemsOnlyInClaude.map((item, i) => (
-                <div key={i} className="text-sm text-slate-400 pl-3 border-l-2 border-warning">
-                  {item}
+  return (
+    <div className="mb-6">
+      <h4 className="text-sm font-semibold text-white mb-2">{title}</h4>
+
+      {added.length === 0 && updated.length === 0 && removed.length === 0 && (
+        <p className="text-slate-400 text-sm">{t('sync.inSync')}</p>
+      )}
+
+      {added.length > 0 && (
+        <div className="mb-3">
+          <h5 className="text-sm font-medium text-success mb-1">
+            {t('sync.added')} ({added.length})
+          </h5>
+          {added.map((name) => (
+            <div key={name} className="text-sm text-slate-400 pl-3 border-l-2 border-success">
+              + {name}
+            </div>
+          ))}
+        </div>
+      )}
+
+      {updated.length > 0 && (
+        <div className="mb-3">
+          <h5 className="text-sm font-medium text-warning mb-1">
+            {t('sync.updated')} ({updated.length})
+          </h5>
+          {updated.map((item) => (
+            <div key={item.name} className="text-sm text-slate-400 pl-3 border-l-2 border-warning mb-1">
+              ~ {item.name}
+              {item.diff.map((field) => (
+                <div key={field.path} className="font-mono text-xs pl-4">
+                  {field.path || '(value)'}:{' '}
+                  <span className="text-error">{format(field.old)}</span>
+                  {' → '}
+                  <span className="text-success">{format(field.new)}</span>
                 </div>
               ))}
             </div>
-          </div>
-        )}
+          ))}
+        </div>
+      )}
 
-        {itemsOnlyInOpencode.length === 0 && itemsOnlyInClaude.length === 0 && (
-          <p className="text-slate-400 text-sm">All MCPs are synchronized.</p>
-        )}
-      </div>
+      {removed.length > 0 && (
+        <div className="mb-3">
+          <h5 className="text-sm font-medium text-error mb-1">
+            {t('sync.removed')} ({removed.length})
+          </h5>
+          {removed.map((name) => (
+            <div key={name} className="text-sm text-slate-400 pl-3 border-l-2 border-error">
+              - {name}
+            </div>
+          ))}
+        </div>
+      )}
     </div>
   );
-}
\ No newline at end of file
+}
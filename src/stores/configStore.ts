@@ -2,24 +2,56 @@ import { create } from 'zustand';
 import {
   MCPItem,
   MCPList,
+  MCPScope,
+  McpSource,
   OhMyOpenCodeConfig,
   ConfigPaths,
+  ConfigPathKey,
+  ClaudeSettings,
+  ClaudeSettingsFile,
+  SettingsScope,
+  SyncDirection,
+  SyncDirectionPreview,
+  BidirectionalSyncReport,
+  HistoryEntry,
+  ServerComparison,
+  ConversionReport,
+  SyncReport,
+  SyncStrategy,
+  getErrorMessage,
 } from '../types';
 import { invoke } from '@tauri-apps/api/core';
 
 interface ConfigStore {
   // State
   mcpList: MCPList;
+  projects: string[];
   skillsConfig: OhMyOpenCodeConfig | null;
   loading: boolean;
   error: string | null;
 
   // MCP Actions
   loadMCPList: () => Promise<void>;
-  addMCP: (name: string, configJson: string, source: 'opencode' | 'claude', description?: string) => Promise<void>;
-  updateMCP: (name: string, configJson: string, source: 'opencode' | 'claude', description?: string) => Promise<void>;
-  deleteMCP: (name: string, source: 'opencode' | 'claude') => Promise<void>;
-  syncMCP: (name: string, fromSource: 'opencode' | 'claude', toSource: 'opencode' | 'claude', configJson: string) => Promise<void>;
+  addMCP: (name: string, configJson: string, source: McpSource, description?: string, scope?: MCPScope, force?: boolean) => Promise<void>;
+  updateMCP: (name: string, configJson: string, source: McpSource, description?: string, scope?: MCPScope, force?: boolean) => Promise<void>;
+  deleteMCP: (name: string, source: McpSource, scope?: MCPScope) => Promise<void>;
+  previewSync: (direction: SyncDirection, names?: string[]) => Promise<SyncDirectionPreview>;
+  compareMCP: (direction: SyncDirection) => Promise<ServerComparison[]>;
+  getSyncHistory: (limit?: number) => Promise<HistoryEntry[]>;
+  undoHistoryEntry: (id: string, force?: boolean) => Promise<HistoryEntry>;
+  syncBidirectional: (direction: SyncDirection, names?: string[], dryRun?: boolean) => Promise<BidirectionalSyncReport>;
+  syncAll: (direction: SyncDirection, strategy: SyncStrategy, names?: string[], dryRun?: boolean) => Promise<SyncReport>;
+  toggleMCP: (name: string, source: McpSource, enabled: boolean, scope?: MCPScope) => Promise<void>;
+  syncMCP: (name: string, fromSource: McpSource, toSource: McpSource, configJson: string, toScope?: MCPScope, force?: boolean, fromScope?: MCPScope) => Promise<ConversionReport>;
+
+  // Project Actions
+  loadProjects: () => Promise<void>;
+  addProject: (path: string) => Promise<void>;
+  removeProject: (path: string) => Promise<void>;
+
+  // Claude Settings Actions
+  getClaudeSettings: (project?: string) => Promise<ClaudeSettingsFile[]>;
+  saveClaudeSettings: (scope: SettingsScope, settings: ClaudeSettings, project?: string) => Promise<string>;
 
   // Skills Actions
   loadSkillsConfig: () => Promise<void>;
@@ -30,13 +62,15 @@ interface ConfigStore {
   toggleSkill: (name: string, enabled: boolean) => Promise<void>;
 
   // Utility
-  getFilteredMCPList: (source: 'opencode' | 'claude') => MCPItem[];
+  getFilteredMCPList: (source: McpSource) => MCPItem[];
   getConfigPaths: () => Promise<ConfigPaths>;
+  setConfigPathOverride: (tool: ConfigPathKey, path: string | null) => Promise<ConfigPaths>;
   clearError: () => void;
 }
 
 export const useConfigStore = create<ConfigStore>((set, get) => ({
-  mcpList: { opencode: [], claude: [] },
+  mcpList: { opencode: [], claude: [], project: [], plugin: [] },
+  projects: [],
   skillsConfig: null,
   loading: false,
   error: null,
@@ -56,12 +90,12 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
       console.error('[ConfigStore] Failed to load MCP list:', error);
       set({
         loading: false,
-        error: error instanceof Error ? error.message : 'Failed to load MCP list'
+        error: getErrorMessage(error, 'Failed to load MCP list')
       });
     }
   },
 
-  addMCP: async (name: string, configJson: string, source: 'opencode' | 'claude', description?: string) => {
+  addMCP: async (name: string, configJson: string, source: McpSource, description?: string, scope?: MCPScope, force?: boolean) => {
     set({ loading: true, error: null });
     try {
       console.log('[ConfigStore] Adding MCP:', name, 'to', source);
@@ -69,20 +103,22 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
         name,
         configJson,
         source,
-        description: description || null
+        description: description || null,
+        scope: scope ?? null,
+        force: force ?? false
       });
       await get().loadMCPList();
     } catch (error) {
       console.error('[ConfigStore] Failed to add MCP:', error);
       set({
         loading: false,
-        error: error instanceof Error ? error.message : 'Failed to add MCP'
+        error: getErrorMessage(error, 'Failed to add MCP')
       });
       throw error;
     }
   },
 
-  updateMCP: async (name: string, configJson: string, source: 'opencode' | 'claude', description?: string) => {
+  updateMCP: async (name: string, configJson: string, source: McpSource, description?: string, scope?: MCPScope, force?: boolean) => {
     set({ loading: true, error: null });
     try {
       console.log('[ConfigStore] Updating MCP:', name, 'in', source);
@@ -90,59 +126,140 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
         name,
         configJson,
         source,
-        description: description || null
+        description: description || null,
+        scope: scope ?? null,
+        force: force ?? false
       });
       await get().loadMCPList();
     } catch (error) {
       console.error('[ConfigStore] Failed to update MCP:', error);
       set({
         loading: false,
-        error: error instanceof Error ? error.message : 'Failed to update MCP'
+        error: getErrorMessage(error, 'Failed to update MCP')
       });
       throw error;
     }
   },
 
-  deleteMCP: async (name: string, source: 'opencode' | 'claude') => {
+  deleteMCP: async (name: string, source: McpSource, scope?: MCPScope) => {
     set({ loading: true, error: null });
     try {
       console.log('[ConfigStore] Deleting MCP:', name, 'from', source);
-      await invoke('delete_mcp', { name, source });
+      await invoke('delete_mcp', { name, source, scope: scope ?? null });
       await get().loadMCPList();
     } catch (error) {
       console.error('[ConfigStore] Failed to delete MCP:', error);
       set({
         loading: false,
-        error: error instanceof Error ? error.message : 'Failed to delete MCP'
+        error: getErrorMessage(error, 'Failed to delete MCP')
       });
       throw error;
     }
   },
 
-  syncMCP: async (name: string, fromSource: 'opencode' | 'claude', toSource: 'opencode' | 'claude', configJson: string) => {
+  previewSync: async (direction: SyncDirection, names?: string[]) => {
+    return await invoke<SyncDirectionPreview>('preview_sync', { direction, names: names ?? null });
+  },
+
+  compareMCP: async (direction: SyncDirection) => {
+    return await invoke<ServerComparison[]>('compare_mcp', { direction });
+  },
+
+  getSyncHistory: async (limit?: number) => {
+    return await invoke<HistoryEntry[]>('get_sync_history', { limit: limit ?? null });
+  },
+
+  undoHistoryEntry: async (id: string, force?: boolean) => {
+    try {
+      const entry = await invoke<HistoryEntry>('undo_history_entry', { id, force: force ?? false });
+      await get().loadMCPList();
+      return entry;
+    } catch (error) {
+      console.error('[ConfigStore] Failed to undo history entry:', error);
+      set({ error: getErrorMessage(error, 'Failed to undo') });
+      throw error;
+    }
+  },
+
+  syncBidirectional: async (direction: SyncDirection, names?: string[], dryRun?: boolean) => {
+    try {
+      const report = await invoke<BidirectionalSyncReport>('sync_bidirectional', {
+        direction,
+        names: names ?? null,
+        dryRun: dryRun ?? false,
+      });
+      if (!report.dry_run) {
+        await get().loadMCPList();
+      }
+      return report;
+    } catch (error) {
+      console.error('[ConfigStore] Failed to sync both ways:', error);
+      set({ error: getErrorMessage(error, 'Failed to sync') });
+      throw error;
+    }
+  },
+
+  syncAll: async (direction: SyncDirection, strategy: SyncStrategy, names?: string[], dryRun?: boolean) => {
+    try {
+      const report = await invoke<SyncReport>('sync_all', {
+        direction,
+        strategy,
+        names: names ?? null,
+        dryRun: dryRun ?? false,
+      });
+      if (!report.dry_run) {
+        await get().loadMCPList();
+      }
+      return report;
+    } catch (error) {
+      console.error('[ConfigStore] Failed to sync:', error);
+      set({ error: getErrorMessage(error, 'Failed to sync') });
+      throw error;
+    }
+  },
+
+  toggleMCP: async (name: string, source: McpSource, enabled: boolean, scope?: MCPScope) => {
+    try {
+      await invoke('toggle_mcp', { name, source, enabled, scope: scope ?? null });
+      await get().loadMCPList();
+    } catch (error) {
+      console.error('[ConfigStore] Failed to toggle MCP:', error);
+      set({ error: getErrorMessage(error, 'Failed to toggle MCP') });
+      throw error;
+    }
+  },
+
+  syncMCP: async (name: string, fromSource: McpSource, toSource: McpSource, configJson: string, toScope?: MCPScope, force?: boolean, fromScope?: MCPScope) => {
     set({ loading: true, error: null });
     try {
       console.log('[ConfigStore] Syncing MCP:', name, 'from', fromSource, 'to', toSource);
-      await invoke('sync_mcp', {
+      const report = await invoke<ConversionReport>('sync_mcp', {
         name,
         fromSource,
         toSource,
-        configJson
+        configJson,
+        fromScope: fromScope ?? null,
+        toScope: toScope ?? null,
+        force: force ?? false
       });
       await get().loadMCPList();
+      return report;
     } catch (error) {
       console.error('[ConfigStore] Failed to sync MCP:', error);
       set({
         loading: false,
-        error: error instanceof Error ? error.message : 'Failed to sync MCP'
+        error: getErrorMessage(error, 'Failed to sync MCP')
       });
       throw error;
     }
   },
 
-  getFilteredMCPList: (source: 'opencode' | 'claude') => {
+  getFilteredMCPList: (source: McpSource) => {
     const { mcpList } = get();
-    return source === 'opencode' ? mcpList.opencode : mcpList.claude;
+    if (source === 'claude') {
+      return [...mcpList.claude, ...mcpList.project, ...mcpList.plugin];
+    }
+    return mcpList[source] ?? [];
   },
 
   // ============================================================================
@@ -181,7 +298,7 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
       await get().loadSkillsConfig();
     } catch (error) {
       set({
-        error: error instanceof Error ? error.message : 'Failed to add skill'
+        error: getErrorMessage(error, 'Failed to add skill')
       });
       throw error;
     }
@@ -209,7 +326,7 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
       set({ skillsConfig: newConfig });
     } catch (error) {
       set({
-        error: error instanceof Error ? error.message : 'Failed to update skill'
+        error: getErrorMessage(error, 'Failed to update skill')
       });
       throw error;
     }
@@ -221,7 +338,7 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
       await get().loadSkillsConfig();
     } catch (error) {
       set({
-        error: error instanceof Error ? error.message : 'Failed to delete skill'
+        error: getErrorMessage(error, 'Failed to delete skill')
       });
       throw error;
     }
@@ -233,12 +350,67 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
       await get().loadSkillsConfig();
     } catch (error) {
       set({
-        error: error instanceof Error ? error.message : 'Failed to toggle skill'
+        error: getErrorMessage(error, 'Failed to toggle skill')
       });
       throw error;
     }
   },
 
+  // ============================================================================
+  // Project Actions
+  // ============================================================================
+
+  loadProjects: async () => {
+    try {
+      const projects = await invoke<string[]>('get_projects');
+      set({ projects });
+    } catch (error) {
+      console.error('[ConfigStore] Failed to load projects:', error);
+    }
+  },
+
+  addProject: async (path: string) => {
+    try {
+      const projects = await invoke<string[]>('add_project', { path });
+      set({ projects });
+      await get().loadMCPList();
+    } catch (error) {
+      console.error('[ConfigStore] Failed to add project:', error);
+      set({ error: getErrorMessage(error, 'Failed to add project') });
+      throw error;
+    }
+  },
+
+  removeProject: async (path: string) => {
+    try {
+      const projects = await invoke<string[]>('remove_project', { path });
+      set({ projects });
+      await get().loadMCPList();
+    } catch (error) {
+      console.error('[ConfigStore] Failed to remove project:', error);
+      set({ error: getErrorMessage(error, 'Failed to remove project') });
+      throw error;
+    }
+  },
+
+  // ============================================================================
+  // Claude Settings Actions
+  // ============================================================================
+
+  getClaudeSettings: async (project?: string) => {
+    return await invoke<ClaudeSettingsFile[]>('get_claude_settings', { project });
+  },
+
+  saveClaudeSettings: async (scope: SettingsScope, settings: ClaudeSettings, project?: string) => {
+    try {
+      return await invoke<string>('save_claude_settings', { scope, project, settings });
+    } catch (error) {
+      console.error('[ConfigStore] Failed to save Claude settings:', error);
+      set({ error: getErrorMessage(error, 'Failed to save Claude settings') });
+      throw error;
+    }
+  },
+
   // ============================================================================
   // Utility
   // ============================================================================
@@ -247,5 +419,11 @@ export const useConfigStore = create<ConfigStore>((set, get) => ({
     return await invoke<ConfigPaths>('get_config_paths');
   },
 
+  setConfigPathOverride: async (tool: ConfigPathKey, path: string | null) => {
+    const paths = await invoke<ConfigPaths>('set_config_path_override', { tool, path });
+    await get().loadMCPList();
+    return paths;
+  },
+
   clearError: () => set({ error: null }),
 }));
\ No newline at end of file
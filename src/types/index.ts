@@ -2,19 +2,51 @@
 // MCP Types - Simplified: name + raw JSON config
 // ============================================================================
 
+/// Tool adapter ids, sent to the backend as `source`
+export type McpSource = 'opencode' | 'claude' | 'claude-desktop' | 'cursor';
+
+export const MCP_SOURCE_LABELS: Record<McpSource, string> = {
+  opencode: 'OpenCode',
+  claude: 'Claude Code',
+  'claude-desktop': 'Claude Desktop',
+  cursor: 'Cursor',
+};
+
+/// Tools that can switch a server off without deleting it
+export const TOGGLEABLE_SOURCES: McpSource[] = ['opencode', 'claude'];
+
+/// Tools with per-project servers (Claude Code's .mcp.json, Cursor's .cursor/mcp.json)
+export const PROJECT_SOURCES: McpSource[] = ['claude', 'cursor'];
+
+/// Where a Claude Code or Cursor MCP server is defined
+/// - user: top-level mcpServers in ~/.claude.json, or ~/.cursor/mcp.json
+/// - local: projects["<path>"].mcpServers in ~/.claude.json
+/// - project: <path>/.mcp.json, or <path>/.cursor/mcp.json for Cursor
+/// - plugin: .mcp.json of an installed Claude Code plugin (read-only)
+export type MCPScope =
+  | { kind: 'user' }
+  | { kind: 'local'; path: string }
+  | { kind: 'project'; path: string }
+  | { kind: 'plugin'; plugin: string; path: string };
+
 /// Single MCP item from backend
 export interface MCPItem {
   name: string;
   config: string;  // Raw JSON string
-  source: 'opencode' | 'claude';
+  source: McpSource;
   enabled: boolean;
   description?: string;
+  scope: MCPScope;
+  read_only: boolean;  // Can be synced elsewhere but not edited
 }
 
 /// MCP list response from backend
 export interface MCPList {
   opencode: MCPItem[];
   claude: MCPItem[];
+  project: MCPItem[];  // From .mcp.json of registered project directories
+  plugin: MCPItem[];   // Contributed by Claude Code plugins, read-only
+  [tool: string]: MCPItem[];  // Every other tool, keyed by its id (e.g. 'claude-desktop')
 }
 
 /// Parsed MCP config for UI display
@@ -96,19 +128,127 @@ export interface OhMyOpenCodeConfig {
 // Sync Types
 // ============================================================================
 
-export type SyncDirection = 'opencode_to_claude' | 'claude_to_opencode';
+/// Two tools by adapter id; one-way syncs copy `from` into `to`
+export interface SyncDirection {
+  from: McpSource;
+  to: McpSource;
+}
+
+/// One differing field; `old` is absent for added fields, `new` for removed ones
+export interface FieldDiff {
+  path: string;  // e.g. "env.TOKEN" or "args[0]"
+  old?: unknown;
+  new?: unknown;
+}
+
+export interface SyncUpdate {
+  name: string;
+  diff: FieldDiff[];
+}
+
+export interface SyncDirectionPreview {
+  added: string[];
+  updated: SyncUpdate[];
+  removed: string[];
+}
+
+/// Same-named user scope servers compared across tools, ignoring format differences
+export type ComparisonStatus = 'identical' | 'differs' | 'only-in-from' | 'only-in-to';
 
-export interface SyncPreview {
-  opencode: {
-    added: string[];
-    updated: string[];
-    removed: string[];
-  };
-  claude: {
-    added: string[];
-    updated: string[];
-    removed: string[];
-  };
+export interface ServerComparison {
+  name: string;
+  status: ComparisonStatus;
+  fields?: FieldDiff[];  // old: `from` tool's value, new: `to` tool's value
+}
+
+/// How sync_all treats servers the target already has in another form
+export type SyncStrategy = 'only-missing' | 'overwrite' | 'skip-on-conflict' | 'prefer-newer';
+
+export type SyncAction = 'added' | 'updated' | 'unchanged' | 'skipped' | 'conflict' | 'invalid';
+
+/// What converting an entry to the other tool's format did to its fields
+export interface ConversionReport {
+  dropped: string[];
+  renamed: { from: string; to: string }[];
+  inferred: string[];
+  restored: string[];
+  warnings: string[];
+}
+
+/// One line per non-empty part of a conversion report
+export function conversionReportLines(report: ConversionReport, t: (key: string) => string): string[] {
+  const lines: string[] = [];
+  const fields: [keyof ConversionReport, string[]][] = [
+    ['dropped', report.dropped],
+    ['renamed', report.renamed.map((r) => `${r.from} → ${r.to}`)],
+    ['inferred', report.inferred],
+    ['restored', report.restored],
+  ];
+  for (const [kind, values] of fields) {
+    if (values.length > 0) {
+      lines.push(`${t(`sync.report.${kind}`)}: ${values.join(', ')}`);
+    }
+  }
+  return lines.concat(report.warnings);
+}
+
+export interface SyncItemResult {
+  name: string;
+  action: SyncAction;
+  diff?: FieldDiff[];
+  report?: ConversionReport;
+  message?: string;
+}
+
+export type MergeOutcome =
+  | 'unchanged'
+  | 'copied_to_right'
+  | 'copied_to_left'
+  | 'deleted_from_right'
+  | 'deleted_from_left'
+  | 'conflict'
+  | 'invalid';
+
+/// Result of a bidirectional sync for one server; conflicts carry both versions
+export interface MergeItem {
+  name: string;
+  outcome: MergeOutcome;
+  left?: unknown;
+  right?: unknown;
+  base?: { left: unknown; right: unknown };
+  report?: ConversionReport;
+  message?: string;
+}
+
+export interface BidirectionalSyncReport {
+  left: McpSource;
+  right: McpSource;
+  dry_run: boolean;
+  items: MergeItem[];
+}
+
+export type HistoryAction = 'sync' | 'add' | 'update' | 'delete' | 'undo';
+
+/// One journaled MCP change, with the entry before and after it
+export interface HistoryEntry {
+  id: string;
+  timestamp: string;
+  action: HistoryAction;
+  source: string;
+  target: string;
+  scope: MCPScope;
+  name: string;
+  before: unknown | null;
+  after: unknown | null;
+  undoes?: string;
+  disabled?: boolean;
+}
+
+export interface SyncReport {
+  direction: SyncDirection;
+  strategy: SyncStrategy;
+  dry_run: boolean;
+  items: SyncItemResult[];
 }
 
 // ============================================================================
@@ -129,11 +269,87 @@ export interface AppSettings {
 // Path Types
 // ============================================================================
 
-export interface ConfigPaths {
-  opencode: string;
-  skills: string;
-  claude: string;
-  backup: string;
+/// Where a resolved path came from
+export type PathOrigin = 'default' | 'env' | 'override';
+
+export interface ResolvedPath {
+  path: string;
+  origin: PathOrigin;
+  variable?: string;  // Environment variable, when origin is 'env'
+}
+
+export type ConfigPathKey = 'opencode' | 'skills' | 'claude' | 'claude-desktop' | 'cursor' | 'backup';
+
+export type ConfigPaths = Record<ConfigPathKey, ResolvedPath>;
+
+// ============================================================================
+// Claude Settings Types
+// ============================================================================
+
+/// user: ~/.claude/settings.json, project: .claude/settings.json, local: .claude/settings.local.json
+export type SettingsScope = 'user' | 'project' | 'local';
+
+export interface ClaudePermissions {
+  allow?: string[];
+  ask?: string[];
+  deny?: string[];
+  additionalDirectories?: string[];
+  defaultMode?: string;
+  [key: string]: unknown;
+}
+
+export interface ClaudeSettings {
+  env?: Record<string, string>;
+  model?: string;
+  permissions?: ClaudePermissions;
+  hooks?: Record<string, unknown>;
+  enableAllProjectMcpServers?: boolean;
+  enabledMcpjsonServers?: string[];
+  disabledMcpjsonServers?: string[];
+  [key: string]: unknown;
+}
+
+export interface ClaudeSettingsFile {
+  scope: SettingsScope;
+  path: string;
+  exists: boolean;
+  settings: ClaudeSettings;
+}
+
+// ============================================================================
+// Error Types
+// ============================================================================
+
+/// Structured error returned by every backend command
+export interface ConfigError {
+  code:
+    | 'file_not_found'
+    | 'permission_denied'
+    | 'read_error'
+    | 'parse_error'
+    | 'write_error'
+    | 'conflict'
+    | 'mcp_not_found'
+    | 'validation_error'
+    | 'invalid_input'
+    | 'internal';
+  message: string;
+  path?: string;
+  line?: number;
+  column?: number;
+  errors?: string[];  // One message per invalid field, for 'validation_error'
+}
+
+export function isConfigError(error: unknown): error is ConfigError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+/// Helper to get a displayable message from a rejected invoke()
+export function getErrorMessage(error: unknown, fallback: string): string {
+  if (isConfigError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  if (typeof error === 'string') return error;
+  return fallback;
 }
 
 // ============================================================================